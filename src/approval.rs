@@ -1,7 +1,37 @@
 use crate::*;
 
+pub const DEFAULT_GAS_FOR_NFT_ON_APPROVE: Gas = Gas(10_000_000_000_000);
+const GAS_FOR_RESOLVE_APPROVE: Gas = Gas(10_000_000_000_000);
+/// Operator approval ids are drawn above every per-token approval id,
+/// so an id given for one kind of approval never matches the other.
+/// 2^52 still fits in a JSON number without losing precision.
+pub(crate) const FIRST_OPERATOR_APPROVAL_ID: u64 = 1 << 52;
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Approval {
+    pub approval_id: u64,
+    /// block timestamp in nanoseconds after which the approval is ignored
+    pub expires_at: Option<U64>,
+}
+
+impl Approval {
+    pub fn is_active(&self) -> bool {
+        match self.expires_at {
+            Some(expires_at) => env::block_timestamp() < expires_at.0,
+            None => true,
+        }
+    }
+}
+
 pub trait NftApproval {
-    fn nft_approve(&mut self, token_id: TokenId, account_id: AccountId, msg: Option<String>);
+    fn nft_approve(
+        &mut self,
+        token_id: TokenId,
+        account_id: AccountId,
+        msg: Option<String>,
+        expires_at: Option<U64>,
+    ) -> PromiseOrValue<bool>;
 
     fn nft_is_approved(
         &self,
@@ -18,8 +48,15 @@ pub trait NftApproval {
 #[near_bindgen]
 impl NftApproval for NftContract {
     #[payable]
-    fn nft_approve(&mut self, token_id: TokenId, account_id: AccountId, msg: Option<String>) {
+    fn nft_approve(
+        &mut self,
+        token_id: TokenId,
+        account_id: AccountId,
+        msg: Option<String>,
+        expires_at: Option<U64>,
+    ) -> PromiseOrValue<bool> {
         assert_at_least_one_yocto();
+        let initial_storage_usage = env::storage_usage();
 
         let mut token = self
             .tokens_by_id
@@ -31,33 +68,80 @@ impl NftApproval for NftContract {
             token.owner_id,
             "Predecessor should be the owner of NFT"
         );
+        self.assert_not_soulbound(&token_id);
+        self.assert_not_locked(&token_id);
+        self.assert_approvable(&account_id);
+
+        if let Some(expires_at) = expires_at {
+            assert!(
+                env::block_timestamp() < expires_at.0,
+                "Approval must expire in the future"
+            );
+        }
+
+        let mut approvals = self.approvals_per_token.get(&token_id).unwrap_or_else(|| {
+            UnorderedMap::new(StorageKey::ApprovalsPerTokenInner {
+                token_id_hash: hash_token_id(&token_id),
+            })
+        });
 
         let approval_id = token.next_approval_id;
 
-        let is_new_approval = token
-            .approved_account_ids
-            .insert(account_id.clone(), approval_id)
+        let is_new_approval = approvals
+            .insert(
+                &account_id,
+                &Approval {
+                    approval_id,
+                    expires_at,
+                },
+            )
             .is_none();
 
-        let storage_used = if is_new_approval {
-            bytes_for_approved_account_id(&account_id)
-        } else {
-            0
-        };
+        if is_new_approval {
+            self.internal_add_approved_token(&account_id, &token_id);
+            assert!(
+                approvals.len() <= self.max_approvals_per_token as u64,
+                "Cannot add more than {} approvals to an NFT",
+                self.max_approvals_per_token
+            );
+        }
+
+        self.approvals_per_token.insert(&token_id, &approvals);
 
         token.next_approval_id += 1;
         self.tokens_by_id.insert(&token_id, &token);
 
-        refund_deposit(storage_used);
+        log_approve(
+            &token_id,
+            &token.owner_id,
+            vec![(account_id.clone(), approval_id)],
+        );
 
-        if let Some(msg) = msg {
-            ext_nft_approval_receiver::ext(account_id).nft_on_approve(
-                token_id,
-                token.owner_id,
-                approval_id,
-                msg,
+        let final_storage_usage = env::storage_usage();
+        if final_storage_usage >= initial_storage_usage {
+            refund_deposit(final_storage_usage - initial_storage_usage);
+        } else {
+            // re-approving without an expiry shrinks the stored approval
+            refund_deposit(0);
+            refund_storage_released(
+                token.owner_id.clone(),
+                initial_storage_usage - final_storage_usage,
             );
         }
+
+        if let Some(msg) = msg {
+            ext_nft_approval_receiver::ext(account_id.clone())
+                .with_static_gas(self.gas_for_nft_on_approve)
+                .nft_on_approve(token_id.clone(), token.owner_id.clone(), approval_id, msg)
+                .then(
+                    Self::ext(env::current_account_id())
+                        .with_static_gas(GAS_FOR_RESOLVE_APPROVE)
+                        .nft_resolve_approve(token_id, token.owner_id, account_id, approval_id),
+                )
+                .into()
+        } else {
+            PromiseOrValue::Value(true)
+        }
     }
 
     fn nft_is_approved(
@@ -71,14 +155,19 @@ impl NftApproval for NftContract {
             .get(&token_id)
             .expect("Token doesn't exist");
 
-        if let Some(actual_approval_id) = token.approved_account_ids.get(&approved_account_id) {
-            if let Some(given_approval_id) = approval_id {
-                *actual_approval_id == given_approval_id
-            } else {
-                true
-            }
+        let token_approval_id = self.internal_active_approval_id(&token_id, &approved_account_id);
+        let operator_approval_id =
+            self.internal_operator_approval_id(&token.owner_id, &approved_account_id);
+
+        if token_approval_id.is_none() && operator_approval_id.is_none() {
+            return false;
+        }
+
+        if let Some(given_approval_id) = approval_id {
+            token_approval_id == Some(given_approval_id)
+                || operator_approval_id == Some(given_approval_id)
         } else {
-            false
+            true
         }
     }
 
@@ -86,7 +175,7 @@ impl NftApproval for NftContract {
     fn nft_revoke(&mut self, token_id: TokenId, account_id: AccountId) {
         assert_one_yocto();
 
-        let mut token = self
+        let token = self
             .tokens_by_id
             .get(&token_id)
             .expect("Token doesn't exists");
@@ -95,9 +184,11 @@ impl NftApproval for NftContract {
 
         assert_eq!(predecessor_id, token.owner_id.clone());
 
-        if token.approved_account_ids.remove(&account_id).is_some() {
-            self.tokens_by_id.insert(&token_id, &token);
-            refund_approved_account_ids_iter(predecessor_id, [account_id].iter());
+        let initial_storage_usage = env::storage_usage();
+
+        if self.internal_revoke_approval(&token_id, &predecessor_id, &account_id) {
+            let storage_released = initial_storage_usage.saturating_sub(env::storage_usage());
+            refund_storage_released(predecessor_id, storage_released);
         }
     }
 
@@ -105,7 +196,7 @@ impl NftApproval for NftContract {
     fn nft_revoke_all_accounts(&mut self, token_id: TokenId) {
         assert_one_yocto();
 
-        let mut token = self
+        let token = self
             .tokens_by_id
             .get(&token_id)
             .expect("Token doesn't exists");
@@ -114,18 +205,537 @@ impl NftApproval for NftContract {
 
         assert_eq!(predecessor_id, token.owner_id.clone());
 
-        if !token.approved_account_ids.is_empty() {
-            let approved_account_ids = &token.approved_account_ids.clone();
+        let (approved_account_ids, storage_released) =
+            self.internal_clear_approvals(&token_id, &predecessor_id);
+
+        if !approved_account_ids.is_empty() {
+            refund_storage_released(predecessor_id, storage_released);
+        }
+    }
+}
+
+#[ext_contract(ext_approval_resolver)]
+pub trait NftApprovalResolver {
+    /// Return `true` if the approval was kept and `false` if it was rolled back
+    fn nft_resolve_approve(
+        &mut self,
+        token_id: TokenId,
+        owner_id: AccountId,
+        account_id: AccountId,
+        approval_id: u64,
+    ) -> bool;
+
+    /// Return `true` if the operator approval was kept and `false` if it was rolled back
+    fn nft_resolve_approve_all(
+        &mut self,
+        owner_id: AccountId,
+        operator_id: AccountId,
+        approval_id: u64,
+    ) -> bool;
+}
+
+#[near_bindgen]
+impl NftApprovalResolver for NftContract {
+    /// Roll back the approval when `nft_on_approve` panics or returns `false`.
+    #[private]
+    fn nft_resolve_approve(
+        &mut self,
+        token_id: TokenId,
+        owner_id: AccountId,
+        account_id: AccountId,
+        approval_id: u64,
+    ) -> bool {
+        assert_eq!(
+            env::promise_results_count(),
+            1,
+            "Promise returns too many results"
+        );
+        if let PromiseResult::Successful(value) = env::promise_result(0) {
+            if !matches!(serde_json::from_slice::<bool>(&value), Ok(false)) {
+                return true;
+            }
+        }
+
+        // the token could have been transferred or approved again meanwhile
+        let is_same_approval = self
+            .approvals_per_token
+            .get(&token_id)
+            .and_then(|approvals| approvals.get(&account_id))
+            .map(|approval| approval.approval_id == approval_id)
+            .unwrap_or(false);
+        let is_same_owner = self
+            .tokens_by_id
+            .get(&token_id)
+            .map(|token| token.owner_id == owner_id)
+            .unwrap_or(false);
+
+        if is_same_approval && is_same_owner {
+            let initial_storage_usage = env::storage_usage();
+            self.internal_revoke_approval(&token_id, &owner_id, &account_id);
+            let storage_released = initial_storage_usage.saturating_sub(env::storage_usage());
+            refund_storage_released(owner_id, storage_released);
+        }
+
+        false
+    }
+
+    /// Roll back the operator approval when `nft_on_approve_all` panics or returns `false`.
+    #[private]
+    fn nft_resolve_approve_all(
+        &mut self,
+        owner_id: AccountId,
+        operator_id: AccountId,
+        approval_id: u64,
+    ) -> bool {
+        assert_eq!(
+            env::promise_results_count(),
+            1,
+            "Promise returns too many results"
+        );
+        if let PromiseResult::Successful(value) = env::promise_result(0) {
+            if !matches!(serde_json::from_slice::<bool>(&value), Ok(false)) {
+                return true;
+            }
+        }
+
+        // the operator could have been revoked or approved again meanwhile
+        if self.internal_operator_approval_id(&owner_id, &operator_id) == Some(approval_id) {
+            let initial_storage_usage = env::storage_usage();
+            self.internal_remove_operator(&owner_id, &operator_id);
+            let storage_released = initial_storage_usage.saturating_sub(env::storage_usage());
+            refund_storage_released(owner_id, storage_released);
+        }
+
+        false
+    }
+}
+
+#[near_bindgen]
+impl NftContract {
+    /// Approve `operator_id` to transfer every NFT the predecessor owns, now and in the future.
+    #[payable]
+    pub fn nft_approve_all(
+        &mut self,
+        operator_id: AccountId,
+        msg: Option<String>,
+    ) -> PromiseOrValue<bool> {
+        assert_at_least_one_yocto();
+        let initial_storage_usage = env::storage_usage();
+
+        let owner_id = env::predecessor_account_id();
+        assert_ne!(owner_id, operator_id, "Owner cannot be its own operator");
+        self.assert_approvable(&operator_id);
+
+        let mut operators = self.operators_per_owner.get(&owner_id).unwrap_or_else(|| {
+            UnorderedMap::new(StorageKey::OperatorsPerOwnerInner {
+                account_id_hash: hash_account_id(&owner_id),
+            })
+        });
+
+        let approval_id = self.next_operator_approval_id;
+        self.next_operator_approval_id += 1;
+
+        operators.insert(&operator_id, &approval_id);
+        self.operators_per_owner.insert(&owner_id, &operators);
+
+        let nft_approve_all_log = EventLog {
+            standard: NFT_STANDARD_NAME.to_string(),
+            version: NFT_METADATA_SPEC.to_string(),
+            event: EventLogVariant::NftApproveAll(vec![NftApproveAllLog {
+                owner_id: owner_id.to_string(),
+                operator_id: operator_id.to_string(),
+                approval_id,
+            }]),
+        };
+
+        env::log_str(&nft_approve_all_log.to_string());
+
+        let storage_used = env::storage_usage().saturating_sub(initial_storage_usage);
+        refund_deposit(storage_used);
+
+        if let Some(msg) = msg {
+            ext_nft_approval_receiver::ext(operator_id.clone())
+                .with_static_gas(self.gas_for_nft_on_approve)
+                .nft_on_approve_all(owner_id.clone(), approval_id, msg)
+                .then(
+                    Self::ext(env::current_account_id())
+                        .with_static_gas(GAS_FOR_RESOLVE_APPROVE)
+                        .nft_resolve_approve_all(owner_id, operator_id, approval_id),
+                )
+                .into()
+        } else {
+            PromiseOrValue::Value(true)
+        }
+    }
+
+    #[payable]
+    pub fn nft_revoke_all(&mut self, operator_id: AccountId) {
+        assert_one_yocto();
+
+        let owner_id = env::predecessor_account_id();
+
+        let initial_storage_usage = env::storage_usage();
 
-            token.approved_account_ids.clear();
-            self.tokens_by_id.insert(&token_id, &token);
+        if self.internal_remove_operator(&owner_id, &operator_id) {
+            let storage_released = initial_storage_usage.saturating_sub(env::storage_usage());
+            refund_storage_released(owner_id, storage_released);
+        }
+    }
+
+    /// Remove expired approvals of the NFT and refund their storage to the owner.
+    pub fn nft_prune_expired_approvals(&mut self, token_id: TokenId) {
+        let token = self
+            .tokens_by_id
+            .get(&token_id)
+            .expect("Token doesn't exist");
 
-            refund_approved_account_ids(predecessor_id, approved_account_ids);
+        let expired_account_ids: Vec<AccountId> = self
+            .approvals_per_token
+            .get(&token_id)
+            .map(|approvals| {
+                approvals
+                    .iter()
+                    .filter(|(_, approval)| !approval.is_active())
+                    .map(|(account_id, _)| account_id)
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        if expired_account_ids.is_empty() {
+            return;
+        }
+
+        let initial_storage_usage = env::storage_usage();
+
+        for account_id in expired_account_ids.iter() {
+            self.internal_revoke_approval(&token_id, &token.owner_id, account_id);
         }
+
+        let storage_released = initial_storage_usage.saturating_sub(env::storage_usage());
+        refund_storage_released(token.owner_id, storage_released);
+    }
+
+    /// Return the approvals of the NFT, including expired ones that are not pruned yet.
+    pub fn nft_approvals(
+        &self,
+        token_id: TokenId,
+        from_index: Option<U128>,
+        limit: Option<u64>,
+    ) -> HashMap<AccountId, Approval> {
+        let approvals = if let Some(approvals) = self.approvals_per_token.get(&token_id) {
+            approvals
+        } else {
+            return HashMap::default();
+        };
+
+        let start = u128::from(from_index.unwrap_or(U128(0)));
+        let limit = limit.unwrap_or(approvals.len());
+
+        approvals
+            .iter()
+            .skip(start as usize)
+            .take(limit as usize)
+            .collect()
+    }
+
+    pub fn set_gas_for_nft_on_approve(&mut self, gas: U64) {
+        self.assert_contract_owner();
+        self.gas_for_nft_on_approve = Gas(gas.0);
+    }
+
+    pub fn set_max_approvals_per_token(&mut self, max_approvals_per_token: u32) {
+        self.assert_contract_owner();
+        self.max_approvals_per_token = max_approvals_per_token;
+    }
+
+    pub fn nft_is_approved_for_all(&self, owner_id: AccountId, operator_id: AccountId) -> bool {
+        self.internal_operator_approval_id(&owner_id, &operator_id)
+            .is_some()
+    }
+
+    /// Return the operators of `owner_id` with their approval ids.
+    pub fn nft_operators(
+        &self,
+        owner_id: AccountId,
+        from_index: Option<U128>,
+        limit: Option<u64>,
+    ) -> HashMap<AccountId, u64> {
+        let operators = if let Some(operators) = self.operators_per_owner.get(&owner_id) {
+            operators
+        } else {
+            return HashMap::default();
+        };
+
+        let start = u128::from(from_index.unwrap_or(U128(0)));
+        let limit = limit.unwrap_or(operators.len());
+
+        operators
+            .iter()
+            .skip(start as usize)
+            .take(limit as usize)
+            .collect()
     }
 }
 
+impl NftContract {
+    pub(crate) fn internal_active_approval_id(
+        &self,
+        token_id: &TokenId,
+        account_id: &AccountId,
+    ) -> Option<u64> {
+        self.approvals_per_token
+            .get(token_id)
+            .and_then(|approvals| approvals.get(account_id))
+            .filter(Approval::is_active)
+            .map(|approval| approval.approval_id)
+    }
+
+    /// Return the active approvals of the NFT as listed in `JsonToken`.
+    pub(crate) fn internal_active_approval_ids(
+        &self,
+        token_id: &TokenId,
+    ) -> HashMap<AccountId, u64> {
+        self.approvals_per_token
+            .get(token_id)
+            .map(|approvals| {
+                approvals
+                    .iter()
+                    .filter(|(_, approval)| approval.is_active())
+                    .map(|(account_id, approval)| (account_id, approval.approval_id))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Remove a single approval of the NFT, return `false` if there was none.
+    pub(crate) fn internal_revoke_approval(
+        &mut self,
+        token_id: &TokenId,
+        owner_id: &AccountId,
+        account_id: &AccountId,
+    ) -> bool {
+        let mut approvals = if let Some(approvals) = self.approvals_per_token.get(token_id) {
+            approvals
+        } else {
+            return false;
+        };
+
+        let approval = if let Some(approval) = approvals.remove(account_id) {
+            approval
+        } else {
+            return false;
+        };
+
+        if approvals.is_empty() {
+            self.approvals_per_token.remove(token_id);
+        } else {
+            self.approvals_per_token.insert(token_id, &approvals);
+        }
+        self.internal_remove_approved_token(account_id, token_id);
+
+        let nft_revoke_log = EventLog {
+            standard: NFT_STANDARD_NAME.to_string(),
+            version: NFT_METADATA_SPEC.to_string(),
+            event: EventLogVariant::NftRevoke(vec![NftRevokeLog {
+                owner_id: owner_id.to_string(),
+                account_id: account_id.to_string(),
+                approval_id: approval.approval_id,
+                token_ids: vec![token_id.to_string()],
+            }]),
+        };
+
+        env::log_str(&nft_revoke_log.to_string());
+
+        true
+    }
+
+    /// Remove every approval of the NFT and return them with the bytes of storage released.
+    pub(crate) fn internal_clear_approvals(
+        &mut self,
+        token_id: &TokenId,
+        owner_id: &AccountId,
+    ) -> (HashMap<AccountId, Approval>, u64) {
+        let initial_storage_usage = env::storage_usage();
+
+        if let Some(mut approvals) = self.approvals_per_token.remove(token_id) {
+            let approved_account_ids: HashMap<AccountId, Approval> = approvals.iter().collect();
+            approvals.clear();
+
+            for account_id in approved_account_ids.keys() {
+                self.internal_remove_approved_token(account_id, token_id);
+            }
+
+            let nft_revoke_all_accounts_log = EventLog {
+                standard: NFT_STANDARD_NAME.to_string(),
+                version: NFT_METADATA_SPEC.to_string(),
+                event: EventLogVariant::NftRevokeAllAccounts(vec![NftRevokeAllAccountsLog {
+                    owner_id: owner_id.to_string(),
+                    account_ids: approved_account_ids
+                        .keys()
+                        .map(|account_id| account_id.to_string())
+                        .collect(),
+                    token_ids: vec![token_id.to_string()],
+                }]),
+            };
+
+            env::log_str(&nft_revoke_all_accounts_log.to_string());
+
+            let storage_released = initial_storage_usage.saturating_sub(env::storage_usage());
+            (approved_account_ids, storage_released)
+        } else {
+            (HashMap::default(), 0)
+        }
+    }
+
+    pub(crate) fn internal_restore_approvals(
+        &mut self,
+        token_id: &TokenId,
+        owner_id: &AccountId,
+        approved_account_ids: HashMap<AccountId, Approval>,
+    ) {
+        if approved_account_ids.is_empty() {
+            return;
+        }
+
+        let mut approvals = UnorderedMap::new(StorageKey::ApprovalsPerTokenInner {
+            token_id_hash: hash_token_id(token_id),
+        });
+        for (account_id, approval) in approved_account_ids.iter() {
+            approvals.insert(account_id, approval);
+            self.internal_add_approved_token(account_id, token_id);
+        }
+        self.approvals_per_token.insert(token_id, &approvals);
+
+        log_approve(
+            token_id,
+            owner_id,
+            approved_account_ids
+                .into_iter()
+                .map(|(account_id, approval)| (account_id, approval.approval_id))
+                .collect(),
+        );
+    }
+
+    pub(crate) fn internal_add_approved_token(
+        &mut self,
+        account_id: &AccountId,
+        token_id: &TokenId,
+    ) {
+        let mut tokens_set = self
+            .tokens_approved_per_account
+            .get(account_id)
+            .unwrap_or_else(|| {
+                UnorderedSet::new(StorageKey::TokensApprovedPerAccountInner {
+                    account_id_hash: hash_account_id(account_id),
+                })
+            });
+        tokens_set.insert(token_id);
+        self.tokens_approved_per_account
+            .insert(account_id, &tokens_set);
+    }
+
+    pub(crate) fn internal_remove_approved_token(
+        &mut self,
+        account_id: &AccountId,
+        token_id: &TokenId,
+    ) {
+        if let Some(mut tokens_set) = self.tokens_approved_per_account.get(account_id) {
+            tokens_set.remove(token_id);
+
+            if tokens_set.is_empty() {
+                self.tokens_approved_per_account.remove(account_id);
+            } else {
+                self.tokens_approved_per_account
+                    .insert(account_id, &tokens_set);
+            }
+        }
+    }
+
+    pub(crate) fn internal_operator_approval_id(
+        &self,
+        owner_id: &AccountId,
+        operator_id: &AccountId,
+    ) -> Option<u64> {
+        self.operators_per_owner
+            .get(owner_id)
+            .and_then(|operators| operators.get(operator_id))
+    }
+
+    /// Remove the operator approval and return whether there was one.
+    pub(crate) fn internal_remove_operator(
+        &mut self,
+        owner_id: &AccountId,
+        operator_id: &AccountId,
+    ) -> bool {
+        let mut operators = if let Some(operators) = self.operators_per_owner.get(owner_id) {
+            operators
+        } else {
+            return false;
+        };
+
+        if operators.remove(operator_id).is_none() {
+            return false;
+        }
+
+        if operators.is_empty() {
+            self.operators_per_owner.remove(owner_id);
+        } else {
+            self.operators_per_owner.insert(owner_id, &operators);
+        }
+
+        let nft_revoke_all_log = EventLog {
+            standard: NFT_STANDARD_NAME.to_string(),
+            version: NFT_METADATA_SPEC.to_string(),
+            event: EventLogVariant::NftRevokeAll(vec![NftRevokeAllLog {
+                owner_id: owner_id.to_string(),
+                operator_id: operator_id.to_string(),
+            }]),
+        };
+
+        env::log_str(&nft_revoke_all_log.to_string());
+
+        true
+    }
+
+    /// Whether `account_id` owns the NFT or is approved for it, per token or as an operator.
+    pub(crate) fn internal_is_owner_or_approved(
+        &self,
+        token_id: &TokenId,
+        token: &Token,
+        account_id: &AccountId,
+    ) -> bool {
+        account_id == &token.owner_id
+            || self
+                .internal_active_approval_id(token_id, account_id)
+                .is_some()
+            || self
+                .internal_operator_approval_id(&token.owner_id, account_id)
+                .is_some()
+    }
+}
+
+fn log_approve(token_id: &TokenId, owner_id: &AccountId, approvals: Vec<(AccountId, u64)>) {
+    let nft_approve_log = EventLog {
+        standard: NFT_STANDARD_NAME.to_string(),
+        version: NFT_METADATA_SPEC.to_string(),
+        event: EventLogVariant::NftApprove(
+            approvals
+                .into_iter()
+                .map(|(account_id, approval_id)| NftApproveLog {
+                    owner_id: owner_id.to_string(),
+                    account_id: account_id.to_string(),
+                    approval_id,
+                    token_ids: vec![token_id.to_string()],
+                })
+                .collect(),
+        ),
+    };
+
+    env::log_str(&nft_approve_log.to_string());
+}
+
 #[ext_contract(ext_nft_approval_receiver)]
+#[allow(dead_code)]
 trait NftApprovalReceiver {
     fn nft_on_approve(
         &mut self,
@@ -134,4 +744,6 @@ trait NftApprovalReceiver {
         approval_id: u64,
         msg: String,
     );
+
+    fn nft_on_approve_all(&mut self, owner_id: AccountId, approval_id: u64, msg: String);
 }
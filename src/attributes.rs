@@ -0,0 +1,214 @@
+use crate::*;
+
+/// make sure storage and gas of a single token stay bounded
+pub const MAX_ATTRIBUTES_PER_TOKEN: usize = 32;
+
+/// Typed on-chain trait of an NFT, serialized as a plain JSON string or number.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde", untagged)]
+pub enum AttributeValue {
+    Number(i64),
+    String(String),
+}
+
+#[near_bindgen]
+impl NftContract {
+    /// Authorize a game contract to update attributes of any NFT.
+    pub fn add_game_contract(&mut self, contract_id: AccountId) {
+        self.assert_contract_owner();
+        self.game_contracts.insert(&contract_id);
+    }
+
+    pub fn remove_game_contract(&mut self, contract_id: AccountId) {
+        self.assert_contract_owner();
+        self.game_contracts.remove(&contract_id);
+    }
+
+    pub fn game_contracts(&self) -> Vec<AccountId> {
+        self.game_contracts.to_vec()
+    }
+
+    /// Set attributes of the NFT, overwriting existing ones with the same name.
+    #[payable]
+    pub fn nft_set_attributes(
+        &mut self,
+        token_id: TokenId,
+        attributes: HashMap<String, AttributeValue>,
+    ) {
+        assert_at_least_one_yocto();
+        self.assert_game_contract();
+        let initial_storage_usage = env::storage_usage();
+
+        let mut token_attributes = self.internal_attributes(&token_id);
+        for (name, value) in attributes.iter() {
+            if let Some(old_value) = token_attributes.insert(name.clone(), value.clone()) {
+                self.internal_remove_token_from_trait(name, &old_value, &token_id);
+            }
+            self.internal_add_token_to_trait(name, value, &token_id);
+        }
+        assert!(
+            token_attributes.len() <= MAX_ATTRIBUTES_PER_TOKEN,
+            "Cannot have more than {} attributes",
+            MAX_ATTRIBUTES_PER_TOKEN
+        );
+        self.attributes_by_id.insert(&token_id, &token_attributes);
+
+        log_attributes_update(&token_id, attributes, vec![]);
+
+        let storage_used = env::storage_usage().saturating_sub(initial_storage_usage);
+        refund_deposit(storage_used);
+    }
+
+    /// Add `amount` to a number attribute, starting from 0 if it isn't set, e.g. to level up.
+    #[payable]
+    pub fn nft_increment_attribute(&mut self, token_id: TokenId, name: String, amount: i64) -> i64 {
+        let value = match self.internal_attributes(&token_id).get(&name) {
+            Some(AttributeValue::Number(value)) => value.checked_add(amount).expect("Overflow"),
+            Some(AttributeValue::String(_)) => env::panic_str("Attribute is not a number"),
+            None => amount,
+        };
+
+        self.nft_set_attributes(
+            token_id,
+            HashMap::from([(name, AttributeValue::Number(value))]),
+        );
+
+        value
+    }
+
+    #[payable]
+    pub fn nft_remove_attributes(&mut self, token_id: TokenId, names: Vec<String>) {
+        assert_one_yocto();
+        self.assert_game_contract();
+        let initial_storage_usage = env::storage_usage();
+
+        let mut token_attributes = self.internal_attributes(&token_id);
+        for name in names.iter() {
+            if let Some(old_value) = token_attributes.remove(name) {
+                self.internal_remove_token_from_trait(name, &old_value, &token_id);
+            }
+        }
+
+        if token_attributes.is_empty() {
+            self.attributes_by_id.remove(&token_id);
+        } else {
+            self.attributes_by_id.insert(&token_id, &token_attributes);
+        }
+
+        log_attributes_update(&token_id, HashMap::new(), names);
+
+        let storage_released = initial_storage_usage.saturating_sub(env::storage_usage());
+        refund_storage_released(env::predecessor_account_id(), storage_released);
+    }
+
+    pub fn nft_attributes(&self, token_id: TokenId) -> HashMap<String, AttributeValue> {
+        self.attributes_by_id.get(&token_id).unwrap_or_default()
+    }
+
+    /// Return the NFTs whose attribute `name` equals `value`.
+    pub fn nft_tokens_by_trait(
+        &self,
+        name: String,
+        value: AttributeValue,
+        from_index: Option<U128>,
+        limit: Option<u64>,
+    ) -> Vec<JsonToken> {
+        let tokens = match self.tokens_by_trait.get(&trait_key(&name, &value)) {
+            Some(tokens) => tokens,
+            None => return vec![],
+        };
+
+        let start = u128::from(from_index.unwrap_or(U128(0)));
+
+        tokens
+            .iter()
+            .skip(start as usize)
+            .take(limit.unwrap_or(50) as usize)
+            .map(|token_id| self.nft_token(token_id).unwrap())
+            .collect()
+    }
+}
+
+impl NftContract {
+    fn assert_game_contract(&self) {
+        assert!(
+            self.game_contracts.contains(&env::predecessor_account_id()),
+            "Predecessor should be an authorized game contract"
+        );
+    }
+
+    fn internal_attributes(&self, token_id: &TokenId) -> HashMap<String, AttributeValue> {
+        assert!(
+            self.tokens_by_id.get(token_id).is_some(),
+            "Token doesn't exist"
+        );
+
+        self.attributes_by_id.get(token_id).unwrap_or_default()
+    }
+
+    fn internal_add_token_to_trait(
+        &mut self,
+        name: &str,
+        value: &AttributeValue,
+        token_id: &TokenId,
+    ) {
+        let key = trait_key(name, value);
+        let mut tokens = self.tokens_by_trait.get(&key).unwrap_or_else(|| {
+            UnorderedSet::new(StorageKey::TokensByTraitInner {
+                trait_hash: env::sha256_array(key.as_bytes()),
+            })
+        });
+        tokens.insert(token_id);
+        self.tokens_by_trait.insert(&key, &tokens);
+    }
+
+    fn internal_remove_token_from_trait(
+        &mut self,
+        name: &str,
+        value: &AttributeValue,
+        token_id: &TokenId,
+    ) {
+        let key = trait_key(name, value);
+        if let Some(mut tokens) = self.tokens_by_trait.get(&key) {
+            tokens.remove(token_id);
+            if tokens.is_empty() {
+                self.tokens_by_trait.remove(&key);
+            } else {
+                self.tokens_by_trait.insert(&key, &tokens);
+            }
+        }
+    }
+
+    /// Drop the attributes of a burned NFT from storage and from the trait index.
+    pub(crate) fn internal_remove_attributes(&mut self, token_id: &TokenId) {
+        if let Some(attributes) = self.attributes_by_id.remove(token_id) {
+            for (name, value) in attributes.iter() {
+                self.internal_remove_token_from_trait(name, value, token_id);
+            }
+        }
+    }
+}
+
+/// `name:value` with the value in JSON so that `1` and `"1"` are different traits.
+fn trait_key(name: &str, value: &AttributeValue) -> String {
+    format!("{}:{}", name, serde_json::to_string(value).unwrap())
+}
+
+fn log_attributes_update(
+    token_id: &TokenId,
+    attributes: HashMap<String, AttributeValue>,
+    removed: Vec<String>,
+) {
+    let nft_attributes_update_log = EventLog {
+        standard: NFT_STANDARD_NAME.to_string(),
+        version: NFT_METADATA_SPEC.to_string(),
+        event: EventLogVariant::NftAttributesUpdate(vec![NftAttributesUpdateLog {
+            token_id: token_id.to_string(),
+            updated_by: env::predecessor_account_id().to_string(),
+            attributes,
+            removed,
+        }]),
+    };
+
+    env::log_str(&nft_attributes_update_log.to_string());
+}
@@ -0,0 +1,236 @@
+use crate::*;
+
+/// bids placed this close to the end push it back by the same amount, in nanoseconds
+pub const AUCTION_EXTENSION_WINDOW: u64 = 10 * 60 * 1_000_000_000;
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Bid {
+    pub bidder_id: AccountId,
+    pub amount: U128,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Auction {
+    pub owner_id: AccountId,
+    /// lowest accepted bid in yoctoNEAR
+    pub reserve_price: U128,
+    /// block timestamp in nanoseconds after which the auction can be settled
+    pub ends_at: U64,
+    /// the top bid is held by the contract until it is outbid or the auction settles
+    pub highest_bid: Option<Bid>,
+}
+
+#[near_bindgen]
+impl NftContract {
+    /// Auction the NFT, it stays locked by the contract until the auction is settled.
+    #[payable]
+    pub fn nft_start_auction(&mut self, token_id: TokenId, reserve_price: U128, ends_at: U64) {
+        assert_at_least_one_yocto();
+        let initial_storage_usage = env::storage_usage();
+
+        let token = self
+            .tokens_by_id
+            .get(&token_id)
+            .expect("Token doesn't exist");
+
+        assert_eq!(
+            env::predecessor_account_id(),
+            token.owner_id,
+            "Predecessor should be the owner of NFT"
+        );
+        self.assert_not_soulbound(&token_id);
+        self.assert_not_locked(&token_id);
+        self.assert_not_attached(&token_id);
+        assert!(
+            env::block_timestamp() < ends_at.0,
+            "Auction must end in the future"
+        );
+
+        self.internal_remove_listing(&token_id);
+        self.internal_lock(&token_id, &env::current_account_id(), u64::MAX, None);
+
+        self.auctions_by_id.insert(
+            &token_id,
+            &Auction {
+                owner_id: token.owner_id.clone(),
+                reserve_price,
+                ends_at,
+                highest_bid: None,
+            },
+        );
+
+        let nft_auction_start_log = EventLog {
+            standard: NFT_STANDARD_NAME.to_string(),
+            version: NFT_METADATA_SPEC.to_string(),
+            event: EventLogVariant::NftAuctionStart(vec![NftAuctionStartLog {
+                owner_id: token.owner_id.to_string(),
+                token_id: token_id.to_string(),
+                reserve_price,
+                ends_at,
+            }]),
+        };
+
+        env::log_str(&nft_auction_start_log.to_string());
+
+        let storage_used = env::storage_usage().saturating_sub(initial_storage_usage);
+        refund_deposit(storage_used);
+    }
+
+    /// Bid the attached deposit, the previous top bidder gets their bid back.
+    #[payable]
+    pub fn nft_bid(&mut self, token_id: TokenId) {
+        let mut auction = self
+            .auctions_by_id
+            .get(&token_id)
+            .expect("Token is not on auction");
+
+        let now = env::block_timestamp();
+        assert!(now < auction.ends_at.0, "Auction has ended");
+
+        let bidder_id = env::predecessor_account_id();
+        assert_ne!(
+            bidder_id, auction.owner_id,
+            "Owner cannot bid on its own NFT"
+        );
+
+        let amount = env::attached_deposit();
+        assert!(
+            amount >= auction.reserve_price.0,
+            "Bid must be at least the reserve price"
+        );
+
+        if let Some(highest_bid) = auction.highest_bid.take() {
+            assert!(
+                amount > highest_bid.amount.0,
+                "Bid must be higher than {}",
+                highest_bid.amount.0
+            );
+            Promise::new(highest_bid.bidder_id).transfer(highest_bid.amount.0);
+        }
+
+        if auction.ends_at.0 - now < AUCTION_EXTENSION_WINDOW {
+            auction.ends_at = U64(now + AUCTION_EXTENSION_WINDOW);
+        }
+
+        auction.highest_bid = Some(Bid {
+            bidder_id: bidder_id.clone(),
+            amount: U128(amount),
+        });
+        self.auctions_by_id.insert(&token_id, &auction);
+
+        let nft_bid_log = EventLog {
+            standard: NFT_STANDARD_NAME.to_string(),
+            version: NFT_METADATA_SPEC.to_string(),
+            event: EventLogVariant::NftBid(vec![NftBidLog {
+                bidder_id: bidder_id.to_string(),
+                token_id: token_id.to_string(),
+                amount: U128(amount),
+                ends_at: auction.ends_at,
+            }]),
+        };
+
+        env::log_str(&nft_bid_log.to_string());
+    }
+
+    /// Close an auction that has ended, anyone can call it.
+    /// The NFT goes to the top bidder and the bid is split according to `nft_payout`.
+    pub fn nft_settle_auction(&mut self, token_id: TokenId) {
+        let auction = self
+            .auctions_by_id
+            .get(&token_id)
+            .expect("Token is not on auction");
+
+        assert!(
+            auction.ends_at.0 <= env::block_timestamp(),
+            "Auction has not ended yet"
+        );
+
+        self.internal_end_auction(&token_id, auction);
+    }
+
+    /// Cancel an auction that has no bid yet.
+    #[payable]
+    pub fn nft_cancel_auction(&mut self, token_id: TokenId) {
+        assert_one_yocto();
+
+        let auction = self
+            .auctions_by_id
+            .get(&token_id)
+            .expect("Token is not on auction");
+
+        assert_eq!(
+            env::predecessor_account_id(),
+            auction.owner_id,
+            "Predecessor should be the owner of NFT"
+        );
+        assert!(
+            auction.highest_bid.is_none(),
+            "Auction with bids cannot be cancelled"
+        );
+
+        self.internal_end_auction(&token_id, auction);
+    }
+
+    pub fn nft_auction(&self, token_id: TokenId) -> Option<Auction> {
+        self.auctions_by_id.get(&token_id)
+    }
+
+    pub fn nft_auctions(
+        &self,
+        from_index: Option<U128>,
+        limit: Option<u64>,
+    ) -> Vec<(TokenId, Auction)> {
+        let start = u128::from(from_index.unwrap_or(U128(0)));
+
+        self.auctions_by_id
+            .iter()
+            .skip(start as usize)
+            .take(limit.unwrap_or(50) as usize)
+            .collect()
+    }
+}
+
+impl NftContract {
+    fn internal_end_auction(&mut self, token_id: &TokenId, auction: Auction) {
+        let initial_storage_usage = env::storage_usage();
+        self.auctions_by_id.remove(token_id);
+        self.internal_unlock(token_id);
+        let storage_released = initial_storage_usage.saturating_sub(env::storage_usage());
+        refund_storage_released(auction.owner_id.clone(), storage_released);
+
+        let winner_id = auction
+            .highest_bid
+            .as_ref()
+            .map(|bid| bid.bidder_id.clone());
+        let price = auction.highest_bid.as_ref().map(|bid| bid.amount);
+
+        if let Some(highest_bid) = auction.highest_bid {
+            let (previous_token, _, storage_released) = self.internal_transfer(
+                &auction.owner_id,
+                &highest_bid.bidder_id,
+                token_id,
+                None,
+                None,
+            );
+
+            refund_storage_released(previous_token.owner_id.clone(), storage_released);
+
+            self.internal_pay_out(token_id, &previous_token, highest_bid.amount.0);
+        }
+
+        let nft_auction_end_log = EventLog {
+            standard: NFT_STANDARD_NAME.to_string(),
+            version: NFT_METADATA_SPEC.to_string(),
+            event: EventLogVariant::NftAuctionEnd(vec![NftAuctionEndLog {
+                owner_id: auction.owner_id.to_string(),
+                token_id: token_id.to_string(),
+                winner_id: winner_id.map(|account_id| account_id.to_string()),
+                price,
+            }]),
+        };
+
+        env::log_str(&nft_auction_end_log.to_string());
+    }
+}
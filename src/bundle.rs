@@ -0,0 +1,124 @@
+use crate::*;
+
+/// make sure GAS enough to move every component
+pub const MAX_BUNDLE_SIZE: usize = 20;
+
+#[near_bindgen]
+impl NftContract {
+    /// Escrow NFTs owned by or approved to the predecessor in the contract
+    /// and mint a bundle NFT that stands for all of them.
+    /// `approval_ids` follow `token_ids` and are checked as in `nft_transfer`.
+    #[payable]
+    pub fn nft_bundle(
+        &mut self,
+        token_ids: Vec<TokenId>,
+        approval_ids: Option<Vec<u64>>,
+    ) -> TokenId {
+        assert_at_least_one_yocto();
+        let initial_storage_usage = env::storage_usage();
+
+        assert!(
+            token_ids.len() > 1 && token_ids.len() <= MAX_BUNDLE_SIZE,
+            "Bundle must have between 2 and {} NFTs",
+            MAX_BUNDLE_SIZE
+        );
+        if let Some(approval_ids) = approval_ids.as_ref() {
+            assert_eq!(
+                approval_ids.len(),
+                token_ids.len(),
+                "Must give one approval id per NFT"
+            );
+        }
+
+        let predecessor_id = env::predecessor_account_id();
+        let bundle_id = format!("bundle:{}", self.next_bundle_id);
+        self.next_bundle_id += 1;
+
+        for (index, token_id) in token_ids.iter().enumerate() {
+            let approval_id = approval_ids
+                .as_ref()
+                .map(|approval_ids| approval_ids[index]);
+            let (previous_token, _, storage_released) = self.internal_transfer(
+                &predecessor_id,
+                &env::current_account_id(),
+                token_id,
+                approval_id,
+                None,
+            );
+            refund_storage_released(previous_token.owner_id, storage_released);
+
+            self.bundle_of_component.insert(token_id, &bundle_id);
+        }
+
+        let metadata = TokenMetadata {
+            title: Some(format!("Bundle of {} NFTs", token_ids.len())),
+            description: None,
+            media: None,
+            media_hash: None,
+            copies: None,
+            issued_at: Some(env::block_timestamp()),
+            expires_at: None,
+            starts_at: None,
+            updated_at: None,
+            extra: Some(serde_json::to_string(&token_ids).unwrap()),
+            reference: None,
+            reference_hash: None,
+        };
+
+        let token = Token {
+            owner_id: predecessor_id,
+            next_approval_id: 0,
+            royalty: HashMap::new(),
+            royalty_schedules: HashMap::new(),
+            uses_default_royalty: false,
+            user: None,
+        };
+
+        self.internal_mint(&bundle_id, &token, &metadata, false);
+        self.bundle_components_by_id.insert(&bundle_id, &token_ids);
+
+        let storage_used = env::storage_usage().saturating_sub(initial_storage_usage);
+        refund_deposit(storage_used);
+
+        bundle_id
+    }
+
+    /// Burn the bundle NFT and hand its components to the bundle owner.
+    #[payable]
+    pub fn nft_unbundle(&mut self, bundle_id: TokenId) {
+        assert_one_yocto();
+
+        let token_ids = self
+            .bundle_components_by_id
+            .get(&bundle_id)
+            .expect("Token is not a bundle");
+
+        let owner_id = self
+            .tokens_by_id
+            .get(&bundle_id)
+            .expect("Token doesn't exist")
+            .owner_id;
+        assert_eq!(
+            env::predecessor_account_id(),
+            owner_id,
+            "Predecessor should be the owner of NFT"
+        );
+
+        self.bundle_components_by_id.remove(&bundle_id);
+        self.internal_burn(&bundle_id, None, None);
+
+        for token_id in token_ids.iter() {
+            self.bundle_of_component.remove(token_id);
+            self.internal_transfer(&env::current_account_id(), &owner_id, token_id, None, None);
+        }
+    }
+
+    pub fn nft_bundle_components(&self, bundle_id: TokenId) -> Option<Vec<TokenId>> {
+        self.bundle_components_by_id.get(&bundle_id)
+    }
+
+    /// Return the bundle the NFT is part of, if any.
+    pub fn nft_bundle_of(&self, token_id: TokenId) -> Option<TokenId> {
+        self.bundle_of_component.get(&token_id)
+    }
+}
@@ -0,0 +1,192 @@
+use crate::*;
+
+pub type RecipeId = u64;
+
+/// Tokens matching an input are burned by the recipe, they must be part of the series
+/// if one is given and have every listed attribute.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RecipeInput {
+    pub series_id: Option<SeriesId>,
+    #[serde(default)]
+    pub attributes: HashMap<String, AttributeValue>,
+    pub quantity: u32,
+}
+
+impl RecipeInput {
+    fn matches(
+        &self,
+        series_id: Option<&SeriesId>,
+        attributes: &HashMap<String, AttributeValue>,
+    ) -> bool {
+        self.series_id
+            .as_ref()
+            .is_none_or(|id| Some(id) == series_id)
+            && self
+                .attributes
+                .iter()
+                .all(|(name, value)| attributes.get(name) == Some(value))
+    }
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Recipe {
+    pub inputs: Vec<RecipeInput>,
+    pub output_series_id: SeriesId,
+    pub enabled: bool,
+    /// how many times the recipe can be crafted, unlimited when `None`
+    pub max_supply: Option<u64>,
+    pub crafted_count: u64,
+}
+
+#[near_bindgen]
+impl NftContract {
+    #[payable]
+    pub fn nft_create_recipe(
+        &mut self,
+        inputs: Vec<RecipeInput>,
+        output_series_id: SeriesId,
+        max_supply: Option<u64>,
+    ) -> U64 {
+        assert_at_least_one_yocto();
+        self.assert_contract_owner();
+        let initial_storage_usage = env::storage_usage();
+
+        assert!(!inputs.is_empty(), "Recipe must have inputs");
+        for input in inputs.iter() {
+            assert!(
+                input.series_id.is_some() || !input.attributes.is_empty(),
+                "Recipe input must require a series or attributes"
+            );
+            if let Some(series_id) = input.series_id.as_ref() {
+                assert!(
+                    self.series_by_id.get(series_id).is_some(),
+                    "Series doesn't exist"
+                );
+            }
+            assert!(input.quantity > 0, "Input quantity must be positive");
+        }
+        assert!(
+            self.series_by_id.get(&output_series_id).is_some(),
+            "Series doesn't exist"
+        );
+
+        let recipe_id = self.next_recipe_id;
+        self.next_recipe_id += 1;
+
+        self.recipes_by_id.insert(
+            &recipe_id,
+            &Recipe {
+                inputs,
+                output_series_id,
+                enabled: true,
+                max_supply,
+                crafted_count: 0,
+            },
+        );
+
+        let storage_used = env::storage_usage().saturating_sub(initial_storage_usage);
+        refund_deposit(storage_used);
+
+        U64(recipe_id)
+    }
+
+    pub fn set_recipe_enabled(&mut self, recipe_id: U64, enabled: bool) {
+        self.assert_contract_owner();
+
+        let mut recipe = self
+            .recipes_by_id
+            .get(&recipe_id.0)
+            .expect("Recipe doesn't exist");
+        recipe.enabled = enabled;
+        self.recipes_by_id.insert(&recipe_id.0, &recipe);
+    }
+
+    /// Burn NFTs of the predecessor matching the recipe inputs and mint its output to them.
+    /// `token_ids` lists the tokens of each input in turn, as many as its quantity.
+    #[payable]
+    pub fn nft_craft(&mut self, recipe_id: U64, token_ids: Vec<TokenId>) -> TokenId {
+        assert_at_least_one_yocto();
+        let initial_storage_usage = env::storage_usage();
+
+        let mut recipe = self
+            .recipes_by_id
+            .get(&recipe_id.0)
+            .expect("Recipe doesn't exist");
+
+        assert!(recipe.enabled, "Recipe is disabled");
+        if let Some(max_supply) = recipe.max_supply {
+            assert!(
+                recipe.crafted_count < max_supply,
+                "Recipe supply is exhausted"
+            );
+        }
+
+        let unique_token_ids: HashSet<&TokenId> = token_ids.iter().collect();
+        assert_eq!(
+            unique_token_ids.len(),
+            token_ids.len(),
+            "Duplicate token ids"
+        );
+
+        let required: u32 = recipe.inputs.iter().map(|input| input.quantity).sum();
+        assert_eq!(
+            token_ids.len(),
+            required as usize,
+            "Tokens don't match the recipe inputs"
+        );
+
+        let predecessor_id = env::predecessor_account_id();
+        let mut remaining_token_ids = token_ids.iter();
+
+        for input in recipe.inputs.iter() {
+            for token_id in remaining_token_ids.by_ref().take(input.quantity as usize) {
+                let token = self
+                    .tokens_by_id
+                    .get(token_id)
+                    .expect("Token doesn't exist");
+                assert_eq!(
+                    token.owner_id, predecessor_id,
+                    "Predecessor should be the owner of NFT"
+                );
+
+                let series_id = self.series_of_token.get(token_id);
+                let attributes = self.attributes_by_id.get(token_id).unwrap_or_default();
+                assert!(
+                    input.matches(series_id.as_ref(), &attributes),
+                    "Tokens don't match the recipe inputs"
+                );
+            }
+        }
+
+        for token_id in token_ids.iter() {
+            self.internal_burn(token_id, None, None);
+        }
+
+        let output_id = self.internal_mint_series(&recipe.output_series_id, &predecessor_id);
+
+        recipe.crafted_count += 1;
+        self.recipes_by_id.insert(&recipe_id.0, &recipe);
+
+        let storage_used = env::storage_usage().saturating_sub(initial_storage_usage);
+        refund_deposit(storage_used);
+
+        output_id
+    }
+
+    pub fn nft_recipe(&self, recipe_id: U64) -> Option<Recipe> {
+        self.recipes_by_id.get(&recipe_id.0)
+    }
+
+    pub fn nft_recipes(&self, from_index: Option<U128>, limit: Option<u64>) -> Vec<(U64, Recipe)> {
+        let start = u128::from(from_index.unwrap_or(U128(0)));
+
+        self.recipes_by_id
+            .iter()
+            .skip(start as usize)
+            .take(limit.unwrap_or(50) as usize)
+            .map(|(recipe_id, recipe)| (U64(recipe_id), recipe))
+            .collect()
+    }
+}
@@ -0,0 +1,265 @@
+use crate::*;
+
+/// Primary sale configured by the contract owner, the price drops by `price_step`
+/// every `step_duration` from `start_price` down to `floor_price`.
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct DutchAuctionConfig {
+    pub start_price: U128,
+    pub floor_price: U128,
+    pub price_step: U128,
+    /// in nanoseconds
+    pub step_duration: U64,
+    pub start_at: U64,
+    pub ends_at: U64,
+    pub max_supply: u64,
+    /// metadata of every minted token, usually the placeholder of a delayed reveal
+    pub metadata: TokenMetadata,
+    /// when set, buyers can claim back what they paid above the clearing price
+    pub rebate: bool,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct DutchAuction {
+    pub start_price: U128,
+    pub floor_price: U128,
+    pub price_step: U128,
+    pub step_duration: U64,
+    pub start_at: U64,
+    pub ends_at: U64,
+    pub max_supply: u64,
+    pub metadata: TokenMetadata,
+    pub rebate: bool,
+    pub minted_count: u64,
+    /// price paid by the last buyer, which is the clearing price once the sale has ended
+    pub last_price: Option<U128>,
+    pub total_paid: U128,
+    pub proceeds_withdrawn: bool,
+}
+
+impl From<DutchAuctionConfig> for DutchAuction {
+    fn from(config: DutchAuctionConfig) -> Self {
+        Self {
+            start_price: config.start_price,
+            floor_price: config.floor_price,
+            price_step: config.price_step,
+            step_duration: config.step_duration,
+            start_at: config.start_at,
+            ends_at: config.ends_at,
+            max_supply: config.max_supply,
+            metadata: config.metadata,
+            rebate: config.rebate,
+            minted_count: 0,
+            last_price: None,
+            total_paid: U128(0),
+            proceeds_withdrawn: false,
+        }
+    }
+}
+
+impl DutchAuction {
+    pub fn price_at(&self, timestamp: u64) -> Balance {
+        if timestamp <= self.start_at.0 {
+            return self.start_price.0;
+        }
+
+        let steps = (timestamp - self.start_at.0) / self.step_duration.0;
+        let drop = self.price_step.0.saturating_mul(steps as u128);
+
+        self.start_price
+            .0
+            .saturating_sub(drop)
+            .max(self.floor_price.0)
+    }
+
+    pub fn has_ended(&self) -> bool {
+        self.ends_at.0 <= env::block_timestamp() || self.minted_count == self.max_supply
+    }
+}
+
+/// What an account paid during a sale with rebates.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct DutchPayment {
+    pub count: u64,
+    pub total: Balance,
+}
+
+#[near_bindgen]
+impl NftContract {
+    pub fn set_dutch_auction(&mut self, config: DutchAuctionConfig) {
+        self.assert_contract_owner();
+        assert!(
+            self.dutch_auction.get().is_none(),
+            "Dutch auction is already configured"
+        );
+        assert!(
+            config.floor_price.0 <= config.start_price.0,
+            "Floor price cannot exceed the start price"
+        );
+        assert!(config.step_duration.0 > 0, "Step duration must be positive");
+        assert!(
+            config.start_at.0 < config.ends_at.0,
+            "Sale must end after it starts"
+        );
+
+        self.dutch_auction.set(&config.into());
+    }
+
+    pub fn nft_dutch_auction(&self) -> Option<DutchAuction> {
+        self.dutch_auction.get()
+    }
+
+    /// Current mint price of the Dutch auction in yoctoNEAR.
+    pub fn nft_dutch_price(&self) -> U128 {
+        let dutch_auction = self
+            .dutch_auction
+            .get()
+            .expect("Dutch auction is not configured");
+
+        U128(dutch_auction.price_at(env::block_timestamp()))
+    }
+
+    /// Mint the next token of the sale at the current price. The deposit must also cover storage.
+    #[payable]
+    pub fn nft_dutch_mint(&mut self) -> TokenId {
+        let mut dutch_auction = self
+            .dutch_auction
+            .get()
+            .expect("Dutch auction is not configured");
+
+        let now = env::block_timestamp();
+        assert!(dutch_auction.start_at.0 <= now, "Sale has not started yet");
+        assert!(!dutch_auction.has_ended(), "Sale has ended");
+
+        let initial_storage_usage = env::storage_usage();
+        let buyer_id = env::predecessor_account_id();
+        let price = dutch_auction.price_at(now);
+        let token_id = format!("dutch:{}", dutch_auction.minted_count);
+
+        let token = Token {
+            owner_id: buyer_id.clone(),
+            next_approval_id: 0,
+            royalty: HashMap::new(),
+            royalty_schedules: HashMap::new(),
+            uses_default_royalty: true,
+            user: None,
+        };
+        self.internal_mint(&token_id, &token, &dutch_auction.metadata, false);
+        self.internal_assign_reveal_index(&token_id);
+
+        if dutch_auction.rebate {
+            let mut payment = self
+                .dutch_payments
+                .get(&buyer_id)
+                .unwrap_or(DutchPayment { count: 0, total: 0 });
+            payment.count += 1;
+            payment.total += price;
+            self.dutch_payments.insert(&buyer_id, &payment);
+        }
+
+        dutch_auction.minted_count += 1;
+        dutch_auction.last_price = Some(U128(price));
+        dutch_auction.total_paid = U128(dutch_auction.total_paid.0 + price);
+        self.dutch_auction.set(&dutch_auction);
+
+        let storage_used = env::storage_usage().saturating_sub(initial_storage_usage);
+        let required = price + env::storage_byte_cost() * Balance::from(storage_used);
+        assert!(
+            env::attached_deposit() >= required,
+            "Must attach {} yoctoNEAR to cover the price and storage",
+            required
+        );
+
+        let refund = env::attached_deposit() - required;
+        if refund > 1 {
+            Promise::new(buyer_id).transfer(refund);
+        }
+
+        token_id
+    }
+
+    /// Amount the account can claim back once the sale has ended.
+    pub fn nft_dutch_rebate_of(&self, account_id: AccountId) -> U128 {
+        let dutch_auction = self
+            .dutch_auction
+            .get()
+            .expect("Dutch auction is not configured");
+
+        U128(self.internal_dutch_rebate(&dutch_auction, &account_id))
+    }
+
+    /// Send the buyer what they paid above the clearing price.
+    pub fn nft_claim_rebate(&mut self) -> U128 {
+        let dutch_auction = self
+            .dutch_auction
+            .get()
+            .expect("Dutch auction is not configured");
+
+        assert!(dutch_auction.rebate, "Sale has no rebates");
+        assert!(dutch_auction.has_ended(), "Sale has not ended yet");
+
+        let account_id = env::predecessor_account_id();
+        let rebate = self.internal_dutch_rebate(&dutch_auction, &account_id);
+
+        let initial_storage_usage = env::storage_usage();
+        self.dutch_payments
+            .remove(&account_id)
+            .expect("Nothing to claim");
+        let storage_released = initial_storage_usage - env::storage_usage();
+
+        let amount = rebate + env::storage_byte_cost() * Balance::from(storage_released);
+        Promise::new(account_id).transfer(amount);
+
+        U128(rebate)
+    }
+
+    /// Send the proceeds of the sale to the contract owner once it has ended.
+    pub fn nft_withdraw_dutch_proceeds(&mut self) -> U128 {
+        self.assert_contract_owner();
+
+        let mut dutch_auction = self
+            .dutch_auction
+            .get()
+            .expect("Dutch auction is not configured");
+
+        assert!(dutch_auction.has_ended(), "Sale has not ended yet");
+        assert!(
+            !dutch_auction.proceeds_withdrawn,
+            "Proceeds are already withdrawn"
+        );
+
+        // with rebates every token is sold at the clearing price
+        let proceeds = if dutch_auction.rebate {
+            dutch_auction.last_price.unwrap_or(U128(0)).0 * dutch_auction.minted_count as u128
+        } else {
+            dutch_auction.total_paid.0
+        };
+
+        dutch_auction.proceeds_withdrawn = true;
+        self.dutch_auction.set(&dutch_auction);
+
+        if proceeds > 0 {
+            Promise::new(self.owner_id.clone()).transfer(proceeds);
+        }
+
+        U128(proceeds)
+    }
+}
+
+impl NftContract {
+    fn internal_dutch_rebate(
+        &self,
+        dutch_auction: &DutchAuction,
+        account_id: &AccountId,
+    ) -> Balance {
+        let clearing_price = match dutch_auction.last_price {
+            Some(last_price) if dutch_auction.has_ended() => last_price.0,
+            _ => return 0,
+        };
+
+        self.dutch_payments.get(account_id).map_or(0, |payment| {
+            payment.total - clearing_price * payment.count as u128
+        })
+    }
+}
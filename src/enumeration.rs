@@ -25,4 +25,28 @@ impl NftContract {
             .map(|token_id| self.nft_token(token_id).unwrap())
             .collect()
     }
+
+    /// Return the NFTs `account_id` holds a per-token approval for, expired ones included.
+    pub fn nft_tokens_approved_for(
+        &self,
+        account_id: AccountId,
+        from_index: Option<U128>,
+        limit: Option<u64>,
+    ) -> Vec<JsonToken> {
+        let tokens = if let Some(tokens_set) = self.tokens_approved_per_account.get(&account_id) {
+            tokens_set
+        } else {
+            return vec![];
+        };
+
+        let start = u128::from(from_index.unwrap_or(U128(0)));
+        let limit = u128::from(limit.unwrap_or(50));
+
+        tokens
+            .iter()
+            .skip(start as usize)
+            .take(limit as usize)
+            .map(|token_id| self.nft_token(token_id).unwrap())
+            .collect()
+    }
 }
@@ -10,6 +10,29 @@ use crate::*;
 pub enum EventLogVariant {
     NftMint(Vec<NftMintLog>),
     NftTransfer(Vec<NftTransferLog>),
+    NftBurn(Vec<NftBurnLog>),
+    NftUpdateUser(Vec<NftUpdateUserLog>),
+    NftLock(Vec<NftLockLog>),
+    NftUnlock(Vec<NftUnlockLog>),
+    NftApproveAll(Vec<NftApproveAllLog>),
+    NftRevokeAll(Vec<NftRevokeAllLog>),
+    NftApprove(Vec<NftApproveLog>),
+    NftRevoke(Vec<NftRevokeLog>),
+    NftRevokeAllAccounts(Vec<NftRevokeAllAccountsLog>),
+    NftRoyaltyUpdate(Vec<NftRoyaltyUpdateLog>),
+    NftList(Vec<NftListLog>),
+    NftDelist(Vec<NftDelistLog>),
+    NftSale(Vec<NftSaleLog>),
+    NftAuctionStart(Vec<NftAuctionStartLog>),
+    NftBid(Vec<NftBidLog>),
+    NftAuctionEnd(Vec<NftAuctionEndLog>),
+    NftOffer(Vec<NftOfferLog>),
+    NftOfferAccept(Vec<NftOfferAcceptLog>),
+    NftOfferWithdraw(Vec<NftOfferWithdrawLog>),
+    NftSwapPropose(Vec<NftSwapProposeLog>),
+    NftSwapAccept(Vec<NftSwapAcceptLog>),
+    NftSwapCancel(Vec<NftSwapCancelLog>),
+    NftAttributesUpdate(Vec<NftAttributesUpdateLog>),
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -54,3 +77,219 @@ pub struct NftTransferLog {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub memo: Option<String>,
 }
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct NftBurnLog {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub authorized_id: Option<String>,
+
+    pub owner_id: String,
+    pub token_ids: Vec<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct NftUpdateUserLog {
+    pub token_id: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<String>,
+
+    pub expires: U64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct NftLockLog {
+    pub token_id: String,
+    pub locker_id: String,
+    pub until: U64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct NftUnlockLog {
+    pub token_id: String,
+    pub locker_id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct NftApproveAllLog {
+    pub owner_id: String,
+    pub operator_id: String,
+    pub approval_id: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct NftRevokeAllLog {
+    pub owner_id: String,
+    pub operator_id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct NftApproveLog {
+    pub owner_id: String,
+    pub account_id: String,
+    pub approval_id: u64,
+    pub token_ids: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct NftRevokeLog {
+    pub owner_id: String,
+    pub account_id: String,
+    pub approval_id: u64,
+    pub token_ids: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct NftRevokeAllAccountsLog {
+    pub owner_id: String,
+    pub account_ids: Vec<String>,
+    pub token_ids: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct NftRoyaltyUpdateLog {
+    pub old_recipient_id: String,
+    pub new_recipient_id: String,
+    pub token_ids: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct NftListLog {
+    pub owner_id: String,
+    pub token_id: String,
+    pub price: U128,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<U64>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct NftDelistLog {
+    pub owner_id: String,
+    pub token_id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct NftSaleLog {
+    pub seller_id: String,
+    pub buyer_id: String,
+    pub token_id: String,
+    pub price: U128,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct NftAuctionStartLog {
+    pub owner_id: String,
+    pub token_id: String,
+    pub reserve_price: U128,
+    pub ends_at: U64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct NftBidLog {
+    pub bidder_id: String,
+    pub token_id: String,
+    pub amount: U128,
+    pub ends_at: U64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct NftAuctionEndLog {
+    pub owner_id: String,
+    pub token_id: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub winner_id: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub price: Option<U128>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct NftOfferLog {
+    pub offer_id: U64,
+    pub bidder_id: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token_id: Option<String>,
+
+    pub amount: U128,
+    pub expires_at: U64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct NftOfferAcceptLog {
+    pub offer_id: U64,
+    pub owner_id: String,
+    pub bidder_id: String,
+    pub token_id: String,
+    pub amount: U128,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct NftOfferWithdrawLog {
+    pub offer_id: U64,
+    pub bidder_id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct NftSwapProposeLog {
+    pub swap_id: U64,
+    pub proposer_id: String,
+    pub counterparty_id: String,
+    pub offered_token_ids: Vec<String>,
+    pub amount: U128,
+    pub requested_token_ids: Vec<String>,
+    pub expires_at: U64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct NftSwapAcceptLog {
+    pub swap_id: U64,
+    pub proposer_id: String,
+    pub counterparty_id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct NftSwapCancelLog {
+    pub swap_id: U64,
+    pub proposer_id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct NftAttributesUpdateLog {
+    pub token_id: String,
+    pub updated_by: String,
+
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub attributes: HashMap<String, AttributeValue>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub removed: Vec<String>,
+}
@@ -1,6 +1,14 @@
 use crate::*;
 
 impl NftContract {
+    pub(crate) fn assert_contract_owner(&self) {
+        assert_eq!(
+            env::predecessor_account_id(),
+            self.owner_id,
+            "Predecessor should be the contract owner"
+        );
+    }
+
     pub(crate) fn internal_add_token_to_owner(
         &mut self,
         account_id: &AccountId,
@@ -8,7 +16,7 @@ impl NftContract {
     ) {
         let mut tokens_set = self.tokens_per_owner.get(account_id).unwrap_or_else(|| {
             UnorderedSet::new(StorageKey::TokenPerOwnerInner {
-                account_id_hash: hash_account_id(&account_id),
+                account_id_hash: hash_account_id(account_id),
             })
         });
         tokens_set.insert(token_id);
@@ -23,7 +31,7 @@ impl NftContract {
         let mut tokens_set = self
             .tokens_per_owner
             .get(account_id)
-            .expect(&format!("{} doesn't own {} NFT", account_id, token_id));
+            .unwrap_or_else(|| panic!("{} doesn't own {} NFT", account_id, token_id));
 
         tokens_set.remove(token_id);
 
@@ -41,21 +49,35 @@ impl NftContract {
         token_id: &TokenId,
         approval_id: Option<u64>,
         memo: Option<String>,
-    ) -> Token {
+    ) -> (Token, HashMap<AccountId, Approval>, u64) {
         let token = self
             .tokens_by_id
             .get(token_id)
             .expect("Token doesn't exist");
 
+        self.assert_transferable(token_id);
+        assert!(
+            self.bundle_of_component.get(token_id).is_none(),
+            "Token is bundled"
+        );
+
         if sender_id != &token.owner_id {
+            // the filter could have changed since the approval was given
+            self.assert_approvable(sender_id);
+
             if let Some(given_approval_id) = approval_id {
-                let actual_approval_id = token
-                    .approved_account_ids
-                    .get(sender_id)
-                    .expect("Sender is not approved");
+                let token_approval_id = self.internal_active_approval_id(token_id, sender_id);
+                let operator_approval_id =
+                    self.internal_operator_approval_id(&token.owner_id, sender_id);
 
-                assert_eq!(
-                    &given_approval_id, actual_approval_id,
+                assert!(
+                    token_approval_id.is_some() || operator_approval_id.is_some(),
+                    "Sender is not approved"
+                );
+
+                assert!(
+                    token_approval_id == Some(given_approval_id)
+                        || operator_approval_id == Some(given_approval_id),
                     "The given approval id is differrent from the actual approval id"
                 );
             } else {
@@ -63,42 +85,74 @@ impl NftContract {
             }
         }
 
+        let authorized_id = approval_id.map(|_| sender_id.clone());
+
+        self.internal_move_token(token_id, token, receiver_id, authorized_id, memo)
+    }
+
+    pub(crate) fn assert_transferable(&self, token_id: &TokenId) {
+        self.assert_not_soulbound(token_id);
+        self.assert_not_locked(token_id);
+        self.assert_not_attached(token_id);
+    }
+
+    /// Moves the token to `receiver_id` once the caller has checked it may,
+    /// logging `authorized_id` as the account that made the transfer.
+    pub(crate) fn internal_move_token(
+        &mut self,
+        token_id: &TokenId,
+        token: Token,
+        receiver_id: &AccountId,
+        authorized_id: Option<AccountId>,
+        memo: Option<String>,
+    ) -> (Token, HashMap<AccountId, Approval>, u64) {
         // assure receiver is not owner
         assert_ne!(
             &token.owner_id, receiver_id,
             "NFT owner and receiver should be different"
         );
 
+        // drop a lock that has already expired
+        self.token_locks.remove(token_id);
+
+        // a listing is made by the previous owner
+        self.internal_remove_listing(token_id);
+
         // remove NFT from sender
         self.internal_remove_token_from_owner(&token.owner_id, token_id);
 
         // add NFT to receiver
         self.internal_add_token_to_owner(receiver_id, token_id);
 
+        // approvals are given by the previous owner and don't move with the token
+        let (approved_account_ids, storage_released) =
+            self.internal_clear_approvals(token_id, &token.owner_id);
+
         // change owner of token
         let transferred_token = Token {
             owner_id: receiver_id.to_owned(),
-            approved_account_ids: HashMap::default(),
             next_approval_id: token.next_approval_id,
             royalty: token.royalty.clone(),
+            royalty_schedules: token.royalty_schedules.clone(),
+            uses_default_royalty: token.uses_default_royalty,
+            user: None,
         };
         self.tokens_by_id.insert(token_id, &transferred_token);
 
-        if let Some(memo) = memo.as_ref() {
-            env::log_str(&format!("Memo: {}", memo))
+        // usage rights don't survive a change of owner
+        if token.user.is_some() {
+            log_update_user(token_id, None, 0);
         }
 
-        let mut authorized_id = None;
-
-        if approval_id.is_some() {
-            authorized_id = Some(sender_id.to_string())
+        if let Some(memo) = memo.as_ref() {
+            env::log_str(&format!("Memo: {}", memo))
         }
 
         let nft_transfer_log = EventLog {
             standard: NFT_STANDARD_NAME.to_string(),
             version: NFT_METADATA_SPEC.to_string(),
             event: EventLogVariant::NftTransfer(vec![NftTransferLog {
-                authorized_id,
+                authorized_id: authorized_id.map(|id| id.to_string()),
                 old_owner_id: token.owner_id.to_string(),
                 new_owner_id: receiver_id.to_string(),
                 token_ids: vec![token_id.to_string()],
@@ -108,6 +162,69 @@ impl NftContract {
 
         env::log_str(&nft_transfer_log.to_string());
 
+        self.internal_move_children(token_id, &token.owner_id, receiver_id);
+
+        (token, approved_account_ids, storage_released)
+    }
+
+    pub(crate) fn internal_burn(
+        &mut self,
+        token_id: &TokenId,
+        authorized_id: Option<String>,
+        memo: Option<String>,
+    ) -> Token {
+        self.assert_not_locked(token_id);
+        assert!(
+            self.bundle_of_component.get(token_id).is_none(),
+            "Token is bundled"
+        );
+        // `nft_unbundle` drops the components before burning the bundle
+        assert!(
+            self.bundle_components_by_id.get(token_id).is_none(),
+            "Bundles must be unbundled instead"
+        );
+
+        let token = self
+            .tokens_by_id
+            .remove(token_id)
+            .expect("Token doesn't exist");
+
+        self.internal_remove_token_from_owner(&token.owner_id, token_id);
+        for recipient_id in token.royalty.keys() {
+            self.internal_remove_token_from_royalty_recipient(recipient_id, token_id);
+        }
+        self.token_metadata_by_id.remove(token_id);
+        self.ft_royalties_by_id.remove(token_id);
+        self.reveal_index_by_id.remove(token_id);
+        self.soulbound_issuer_by_id.remove(token_id);
+        self.token_locks.remove(token_id);
+        self.internal_remove_listing(token_id);
+        self.internal_cancel_offers_for_token(token_id);
+        self.internal_remove_from_tree(token_id);
+        self.series_of_token.remove(token_id);
+        self.internal_remove_attributes(token_id);
+
+        let (_, storage_released) = self.internal_clear_approvals(token_id, &token.owner_id);
+        refund_storage_released(token.owner_id.clone(), storage_released);
+
+        let nft_burn_log = EventLog {
+            standard: NFT_STANDARD_NAME.to_string(),
+            version: NFT_METADATA_SPEC.to_string(),
+            event: EventLogVariant::NftBurn(vec![NftBurnLog {
+                authorized_id,
+                owner_id: token.owner_id.to_string(),
+                token_ids: vec![token_id.to_string()],
+                memo,
+            }]),
+        };
+
+        env::log_str(&nft_burn_log.to_string());
+
+        // burning a wrapped token sends the original back to its owner
+        if let Some(wrapped_token) = self.wrapped_tokens_by_id.remove(token_id) {
+            self.internal_release_wrapped(token_id, &token.owner_id, wrapped_token);
+        }
+
         token
     }
 }
@@ -127,28 +244,14 @@ pub(crate) fn refund_deposit(storage_used: u64) {
     }
 }
 
-pub(crate) fn refund_approved_account_ids_iter<'a, I>(
-    account_id: AccountId,
-    approved_account_ids: I,
-) -> Promise
-where
-    I: Iterator<Item = &'a AccountId>,
-{
-    let storage_released: u64 = approved_account_ids
-        .map(bytes_for_approved_account_id)
-        .sum();
+pub(crate) fn refund_storage_released(account_id: AccountId, storage_released: u64) -> Promise {
     Promise::new(account_id).transfer(Balance::from(storage_released) * env::storage_byte_cost())
 }
 
-pub(crate) fn refund_approved_account_ids(
-    account_id: AccountId,
-    approved_account_ids: &HashMap<AccountId, u64>,
-) -> Promise {
-    refund_approved_account_ids_iter(account_id, approved_account_ids.keys())
-}
-
-pub(crate) fn bytes_for_approved_account_id(account_id: &AccountId) -> u64 {
-    account_id.as_str().len() as u64 + 4 + size_of::<u64>() as u64
+pub(crate) fn hash_token_id(token_id: &TokenId) -> CryptoHash {
+    let mut hash = CryptoHash::default();
+    hash.copy_from_slice(&env::sha256(token_id.as_bytes()));
+    hash
 }
 
 pub(crate) fn hash_account_id(account_id: &AccountId) -> CryptoHash {
@@ -1,9 +1,8 @@
-use std::collections::HashMap;
-use std::mem::size_of;
+use std::collections::{HashMap, HashSet};
 
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
-use near_sdk::collections::{LazyOption, LookupMap, UnorderedSet};
-use near_sdk::json_types::{Base64VecU8, U128};
+use near_sdk::collections::{LazyOption, LookupMap, UnorderedMap, UnorderedSet, Vector};
+use near_sdk::json_types::{Base64VecU8, U128, U64};
 use near_sdk::serde::{Deserialize, Serialize};
 use near_sdk::{
     env, ext_contract, near_bindgen, serde_json, AccountId, Balance, BorshStorageKey, CryptoHash,
@@ -11,25 +10,56 @@ use near_sdk::{
 };
 
 mod approval;
+mod attributes;
+mod auction;
+mod bundle;
+mod craft;
+mod dutch_auction;
 mod enumeration;
 mod events;
 mod internal;
+mod lock;
+mod market;
 mod metadata;
 mod mint;
+mod nesting;
 mod nft_core;
+mod offer;
+mod operator_filter;
+mod rental;
+mod reveal;
 mod royalty;
+mod series;
+mod soulbound;
+mod swap;
+mod wrap;
 
 pub use crate::approval::*;
-pub use crate::enumeration::*;
+pub use crate::attributes::*;
+pub use crate::auction::*;
+pub use crate::bundle::*;
+pub use crate::craft::*;
+pub use crate::dutch_auction::*;
 pub use crate::events::*;
 use crate::internal::*;
+pub use crate::lock::*;
+pub use crate::market::*;
 pub use crate::metadata::*;
 pub use crate::mint::*;
+pub use crate::nesting::*;
 pub use crate::nft_core::*;
+pub use crate::offer::*;
+pub use crate::operator_filter::*;
+pub use crate::rental::*;
+pub use crate::reveal::*;
 pub use crate::royalty::*;
+pub use crate::series::*;
+pub use crate::swap::*;
+pub use crate::wrap::*;
 
 pub const NFT_METADATA_SPEC: &str = "1.0.0";
 pub const NFT_STANDARD_NAME: &str = "nep171";
+pub const DEFAULT_MAX_APPROVALS_PER_TOKEN: u32 = 32;
 
 #[near_bindgen]
 #[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
@@ -43,6 +73,94 @@ pub struct NftContract {
     pub tokens_by_id: LookupMap<TokenId, Token>,
 
     pub token_metadata_by_id: LookupMap<TokenId, TokenMetadata>,
+
+    pub delayed_reveal: LazyOption<DelayedReveal>,
+
+    pub reveal_index_by_id: LookupMap<TokenId, u64>,
+
+    pub revealed_metadata: Vector<TokenMetadata>,
+
+    pub soulbound_issuer_by_id: LookupMap<TokenId, AccountId>,
+
+    pub token_locks: LookupMap<TokenId, TokenLock>,
+
+    pub operators_per_owner: LookupMap<AccountId, UnorderedMap<AccountId, u64>>,
+
+    pub next_operator_approval_id: u64,
+
+    pub approvals_per_token: LookupMap<TokenId, UnorderedMap<AccountId, Approval>>,
+
+    pub max_approvals_per_token: u32,
+
+    pub tokens_approved_per_account: LookupMap<AccountId, UnorderedSet<TokenId>>,
+
+    pub gas_for_nft_on_approve: Gas,
+
+    pub approval_filter_mode: ApprovalFilterMode,
+
+    pub approval_filter_accounts: UnorderedSet<AccountId>,
+
+    pub payout_transfers_only: bool,
+
+    pub tokens_per_royalty_recipient: LookupMap<AccountId, UnorderedSet<TokenId>>,
+
+    pub default_royalty: HashMap<AccountId, u32>,
+
+    pub default_royalty_frozen: bool,
+
+    pub ft_payout_contracts: UnorderedSet<AccountId>,
+
+    pub ft_royalties_by_id: LookupMap<TokenId, HashMap<AccountId, HashMap<AccountId, u32>>>,
+
+    pub listings_by_id: UnorderedMap<TokenId, Listing>,
+
+    pub auctions_by_id: UnorderedMap<TokenId, Auction>,
+
+    pub dutch_auction: LazyOption<DutchAuction>,
+
+    pub dutch_payments: LookupMap<AccountId, DutchPayment>,
+
+    pub offers_by_id: LookupMap<OfferId, Offer>,
+
+    pub next_offer_id: OfferId,
+
+    pub offers_per_token: LookupMap<TokenId, UnorderedSet<OfferId>>,
+
+    pub collection_offers: UnorderedSet<OfferId>,
+
+    pub offers_per_bidder: LookupMap<AccountId, UnorderedSet<OfferId>>,
+
+    pub swaps_by_id: UnorderedMap<SwapId, Swap>,
+
+    pub next_swap_id: SwapId,
+
+    pub wrap_contracts: UnorderedSet<AccountId>,
+
+    pub wrapped_tokens_by_id: LookupMap<TokenId, WrappedToken>,
+
+    pub bundle_components_by_id: LookupMap<TokenId, Vec<TokenId>>,
+
+    pub bundle_of_component: LookupMap<TokenId, TokenId>,
+
+    pub next_bundle_id: u64,
+
+    pub parent_by_id: LookupMap<TokenId, TokenId>,
+
+    pub children_by_id: LookupMap<TokenId, UnorderedSet<TokenId>>,
+
+    pub series_by_id: LookupMap<SeriesId, Series>,
+
+    pub series_of_token: LookupMap<TokenId, SeriesId>,
+
+    pub recipes_by_id: UnorderedMap<RecipeId, Recipe>,
+
+    pub next_recipe_id: RecipeId,
+
+    pub game_contracts: UnorderedSet<AccountId>,
+
+    pub attributes_by_id: LookupMap<TokenId, HashMap<String, AttributeValue>>,
+
+    pub tokens_by_trait: LookupMap<String, UnorderedSet<TokenId>>,
 }
 
 #[derive(BorshSerialize, BorshStorageKey)]
@@ -52,18 +170,117 @@ pub enum StorageKey {
     TokensById,
     TokenMetadataById,
     NFTContractMetadata,
+    DelayedReveal,
+    RevealIndexById,
+    RevealedMetadata,
+    SoulboundIssuerById,
+    TokenLocks,
+    OperatorsPerOwner,
+    OperatorsPerOwnerInner { account_id_hash: CryptoHash },
+    ApprovalsPerToken,
+    ApprovalsPerTokenInner { token_id_hash: CryptoHash },
+    TokensApprovedPerAccount,
+    TokensApprovedPerAccountInner { account_id_hash: CryptoHash },
+    ApprovalFilterAccounts,
+    TokensPerRoyaltyRecipient,
+    TokensPerRoyaltyRecipientInner { account_id_hash: CryptoHash },
+    FtPayoutContracts,
+    FtRoyaltiesById,
+    ListingsById,
+    AuctionsById,
+    DutchAuction,
+    DutchPayments,
+    OffersById,
+    OffersPerToken,
+    OffersPerTokenInner { token_id_hash: CryptoHash },
+    CollectionOffers,
+    OffersPerBidder,
+    OffersPerBidderInner { account_id_hash: CryptoHash },
+    SwapsById,
+    WrapContracts,
+    WrappedTokensById,
+    BundleComponentsById,
+    BundleOfComponent,
+    ParentById,
+    ChildrenById,
+    ChildrenByIdInner { token_id_hash: CryptoHash },
+    SeriesById,
+    SeriesOfToken,
+    RecipesById,
+    GameContracts,
+    AttributesById,
+    TokensByTrait,
+    TokensByTraitInner { trait_hash: CryptoHash },
 }
 
 #[near_bindgen]
 impl NftContract {
     #[init]
-    pub fn new(owner_id: AccountId, metadata: NFTContractMetadata) -> Self {
+    pub fn new(
+        owner_id: AccountId,
+        metadata: NFTContractMetadata,
+        delayed_reveal: Option<DelayedRevealConfig>,
+        default_royalty: Option<HashMap<AccountId, u32>>,
+    ) -> Self {
+        let default_royalty = default_royalty.unwrap_or_default();
+        assert_valid_royalty(&default_royalty);
+        if let Some(delayed_reveal) = delayed_reveal.as_ref() {
+            assert!(delayed_reveal.supply > 0, "Drop supply must be positive");
+        }
+
         Self {
             owner_id,
             metadata: LazyOption::new(StorageKey::NFTContractMetadata, Some(&metadata)),
             tokens_per_owner: LookupMap::new(StorageKey::TokensPerOwner),
             tokens_by_id: LookupMap::new(StorageKey::TokensById),
             token_metadata_by_id: LookupMap::new(StorageKey::TokenMetadataById),
+            delayed_reveal: LazyOption::new(
+                StorageKey::DelayedReveal,
+                delayed_reveal.map(DelayedReveal::from).as_ref(),
+            ),
+            reveal_index_by_id: LookupMap::new(StorageKey::RevealIndexById),
+            revealed_metadata: Vector::new(StorageKey::RevealedMetadata),
+            soulbound_issuer_by_id: LookupMap::new(StorageKey::SoulboundIssuerById),
+            token_locks: LookupMap::new(StorageKey::TokenLocks),
+            operators_per_owner: LookupMap::new(StorageKey::OperatorsPerOwner),
+            next_operator_approval_id: FIRST_OPERATOR_APPROVAL_ID,
+            approvals_per_token: LookupMap::new(StorageKey::ApprovalsPerToken),
+            max_approvals_per_token: DEFAULT_MAX_APPROVALS_PER_TOKEN,
+            tokens_approved_per_account: LookupMap::new(StorageKey::TokensApprovedPerAccount),
+            gas_for_nft_on_approve: DEFAULT_GAS_FOR_NFT_ON_APPROVE,
+            approval_filter_mode: ApprovalFilterMode::Disabled,
+            approval_filter_accounts: UnorderedSet::new(StorageKey::ApprovalFilterAccounts),
+            payout_transfers_only: false,
+            tokens_per_royalty_recipient: LookupMap::new(StorageKey::TokensPerRoyaltyRecipient),
+            default_royalty,
+            default_royalty_frozen: false,
+            ft_payout_contracts: UnorderedSet::new(StorageKey::FtPayoutContracts),
+            ft_royalties_by_id: LookupMap::new(StorageKey::FtRoyaltiesById),
+            listings_by_id: UnorderedMap::new(StorageKey::ListingsById),
+            auctions_by_id: UnorderedMap::new(StorageKey::AuctionsById),
+            dutch_auction: LazyOption::new(StorageKey::DutchAuction, None),
+            dutch_payments: LookupMap::new(StorageKey::DutchPayments),
+            offers_by_id: LookupMap::new(StorageKey::OffersById),
+            next_offer_id: 0,
+            offers_per_token: LookupMap::new(StorageKey::OffersPerToken),
+            collection_offers: UnorderedSet::new(StorageKey::CollectionOffers),
+            offers_per_bidder: LookupMap::new(StorageKey::OffersPerBidder),
+            swaps_by_id: UnorderedMap::new(StorageKey::SwapsById),
+            next_swap_id: 0,
+            wrap_contracts: UnorderedSet::new(StorageKey::WrapContracts),
+            wrapped_tokens_by_id: LookupMap::new(StorageKey::WrappedTokensById),
+            bundle_components_by_id: LookupMap::new(StorageKey::BundleComponentsById),
+            bundle_of_component: LookupMap::new(StorageKey::BundleOfComponent),
+            next_bundle_id: 0,
+            parent_by_id: LookupMap::new(StorageKey::ParentById),
+            children_by_id: LookupMap::new(StorageKey::ChildrenById),
+            series_by_id: LookupMap::new(StorageKey::SeriesById),
+            series_of_token: LookupMap::new(StorageKey::SeriesOfToken),
+            recipes_by_id: UnorderedMap::new(StorageKey::RecipesById),
+            next_recipe_id: 0,
+            game_contracts: UnorderedSet::new(StorageKey::GameContracts),
+            attributes_by_id: LookupMap::new(StorageKey::AttributesById),
+            tokens_by_trait: LookupMap::new(StorageKey::TokensByTrait),
         }
     }
 
@@ -80,6 +297,8 @@ impl NftContract {
                 reference: None,
                 reference_hash: None,
             },
+            None,
+            None,
         )
     }
 }
@@ -87,14 +306,14 @@ impl NftContract {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use near_sdk::test_utils::test_env::alice;
-    use near_sdk::test_utils::VMContextBuilder;
-    use near_sdk::{testing_env, ONE_NEAR};
+    use near_sdk::test_utils::test_env::{alice, bob, carol};
+    use near_sdk::test_utils::{get_logs, VMContextBuilder};
+    use near_sdk::{testing_env, RuntimeFeesConfig, VMConfig, ONE_NEAR};
 
     fn get_context(is_view: bool) -> VMContextBuilder {
         let mut builder = VMContextBuilder::new();
         builder
-            .account_balance(10)
+            .account_balance(10 * ONE_NEAR)
             .current_account_id(alice())
             .signer_account_id(alice())
             .predecessor_account_id(alice())
@@ -107,8 +326,12 @@ mod tests {
     }
 
     fn mint_nft(contract: &mut NftContract, account_id: AccountId, token_id: TokenId) {
-        let token_metadata = TokenMetadata {
-            title: None,
+        contract.nft_mint(token_id, token_metadata(None), account_id, None, None, None);
+    }
+
+    fn token_metadata(title: Option<String>) -> TokenMetadata {
+        TokenMetadata {
+            title,
             description: None,
             media: None,
             media_hash: None,
@@ -120,9 +343,7 @@ mod tests {
             extra: None,
             reference: None,
             reference_hash: None,
-        };
-
-        contract.nft_mint(token_id, token_metadata, account_id, None);
+        }
     }
 
     #[test]
@@ -154,4 +375,1666 @@ mod tests {
         assert!(json_token.is_some());
         assert_eq!(json_token.unwrap().owner_id, alice());
     }
+
+    #[test]
+    fn test_delayed_reveal() {
+        let mut context = get_context(false);
+        context.attached_deposit(ONE_NEAR);
+
+        testing_env!(context.build());
+
+        let metadata_list: Vec<TokenMetadata> = (0..3)
+            .map(|i| token_metadata(Some(format!("final#{}", i))))
+            .collect();
+        let provenance_hash = env::sha256(&serde_json::to_vec(&metadata_list).unwrap());
+
+        let mut contract = NftContract::new(
+            alice(),
+            init_nft_contract().nft_metadata(),
+            Some(DelayedRevealConfig {
+                provenance_hash: provenance_hash.into(),
+                placeholder: token_metadata(Some("hidden".to_string())),
+                supply: 3,
+            }),
+            None,
+        );
+
+        mint_nft(&mut contract, alice(), "token#1".to_string());
+        mint_nft(&mut contract, alice(), "token#2".to_string());
+        let json_token = contract.nft_token("token#1".to_string()).unwrap();
+        assert_eq!(json_token.metadata.title, Some("hidden".to_string()));
+
+        contract.nft_reveal(metadata_list);
+
+        let offset = contract.nft_reveal_info().unwrap().offset.unwrap();
+        let json_token = contract.nft_token("token#2".to_string()).unwrap();
+        assert_eq!(
+            json_token.metadata.title,
+            Some(format!("final#{}", (1 + offset) % 3))
+        );
+
+        // slots left after the reveal are still taken from the committed list
+        mint_nft(&mut contract, alice(), "token#3".to_string());
+        let json_token = contract.nft_token("token#3".to_string()).unwrap();
+        assert_eq!(
+            json_token.metadata.title,
+            Some(format!("final#{}", (2 + offset) % 3))
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Drop is sold out")]
+    fn test_delayed_reveal_supply() {
+        let mut context = get_context(false);
+        context.attached_deposit(ONE_NEAR);
+
+        testing_env!(context.build());
+
+        let mut contract = NftContract::new(
+            alice(),
+            init_nft_contract().nft_metadata(),
+            Some(DelayedRevealConfig {
+                provenance_hash: vec![0; 32].into(),
+                placeholder: token_metadata(None),
+                supply: 1,
+            }),
+            None,
+        );
+
+        mint_nft(&mut contract, alice(), "token#1".to_string());
+        mint_nft(&mut contract, alice(), "token#2".to_string());
+    }
+
+    #[test]
+    #[should_panic(expected = "Drop supply must be positive")]
+    fn test_delayed_reveal_empty_supply() {
+        let context = get_context(false);
+        testing_env!(context.build());
+
+        NftContract::new(
+            alice(),
+            init_nft_contract().nft_metadata(),
+            Some(DelayedRevealConfig {
+                provenance_hash: vec![0; 32].into(),
+                placeholder: token_metadata(None),
+                supply: 0,
+            }),
+            None,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Soulbound token cannot be transferred or approved")]
+    fn test_soulbound_transfer() {
+        let mut context = get_context(false);
+        context.attached_deposit(ONE_NEAR);
+
+        testing_env!(context.build());
+
+        let mut contract = init_nft_contract();
+        contract.nft_mint(
+            "token#1".to_string(),
+            token_metadata(None),
+            bob(),
+            None,
+            Some(true),
+            None,
+        );
+
+        testing_env!(context
+            .predecessor_account_id(bob())
+            .attached_deposit(1)
+            .build());
+        contract.nft_transfer(carol(), "token#1".to_string(), None, None);
+    }
+
+    #[test]
+    fn test_soulbound_recover_and_revoke() {
+        let mut context = get_context(false);
+        context.attached_deposit(ONE_NEAR);
+
+        testing_env!(context.build());
+
+        let mut contract = init_nft_contract();
+        contract.nft_mint(
+            "token#1".to_string(),
+            token_metadata(None),
+            bob(),
+            None,
+            Some(true),
+            None,
+        );
+        assert!(contract.nft_is_soulbound("token#1".to_string()));
+
+        testing_env!(context.attached_deposit(1).build());
+        contract.nft_recover("token#1".to_string(), carol());
+        let json_token = contract.nft_token("token#1".to_string()).unwrap();
+        assert_eq!(json_token.owner_id, carol());
+
+        contract.nft_burn("token#1".to_string(), None);
+        assert!(contract.tokens_by_id.get(&"token#1".to_string()).is_none());
+        assert!(!contract.nft_is_soulbound("token#1".to_string()));
+    }
+
+    #[test]
+    #[should_panic(expected = "Token is locked")]
+    fn test_soulbound_recover_locked() {
+        let mut context = get_context(false);
+        context.attached_deposit(ONE_NEAR);
+
+        testing_env!(context.build());
+
+        let mut contract = init_nft_contract();
+        contract.nft_mint(
+            "token#1".to_string(),
+            token_metadata(None),
+            bob(),
+            None,
+            Some(true),
+            None,
+        );
+        contract.internal_lock(&"token#1".to_string(), &carol(), u64::MAX, None);
+
+        testing_env!(context.attached_deposit(1).build());
+        contract.nft_recover("token#1".to_string(), carol());
+    }
+
+    #[test]
+    fn test_nft_user_expires() {
+        let mut context = get_context(false);
+        context.attached_deposit(ONE_NEAR);
+
+        testing_env!(context.build());
+
+        let mut contract = init_nft_contract();
+        mint_nft(&mut contract, alice(), "token#1".to_string());
+
+        testing_env!(context.block_timestamp(100).build());
+        contract.nft_set_user("token#1".to_string(), Some(bob()), U64(200));
+        assert_eq!(contract.nft_user_of("token#1".to_string()), Some(bob()));
+
+        testing_env!(context.block_timestamp(200).is_view(true).build());
+        assert_eq!(contract.nft_user_of("token#1".to_string()), None);
+    }
+
+    #[test]
+    fn test_nft_user_cleared_on_transfer() {
+        let mut context = get_context(false);
+        context.attached_deposit(ONE_NEAR);
+
+        testing_env!(context.build());
+
+        let mut contract = init_nft_contract();
+        mint_nft(&mut contract, alice(), "token#1".to_string());
+        contract.nft_set_user("token#1".to_string(), Some(bob()), U64(u64::MAX));
+
+        testing_env!(context.attached_deposit(1).build());
+        contract.nft_transfer(carol(), "token#1".to_string(), None, None);
+        assert_eq!(contract.nft_user_of("token#1".to_string()), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Token is locked")]
+    fn test_locked_transfer() {
+        let mut context = get_context(false);
+        context.attached_deposit(ONE_NEAR);
+
+        testing_env!(context.build());
+
+        let mut contract = init_nft_contract();
+        mint_nft(&mut contract, alice(), "token#1".to_string());
+        contract.nft_lock("token#1".to_string(), bob(), U64(u64::MAX));
+
+        testing_env!(context.attached_deposit(1).build());
+        contract.nft_transfer(carol(), "token#1".to_string(), None, None);
+    }
+
+    #[test]
+    fn test_locker_seizes_token() {
+        let mut context = get_context(false);
+        context.attached_deposit(ONE_NEAR);
+
+        testing_env!(context.build());
+
+        let mut contract = init_nft_contract();
+        mint_nft(&mut contract, alice(), "token#1".to_string());
+        contract.nft_lock("token#1".to_string(), bob(), U64(u64::MAX));
+
+        testing_env!(context
+            .predecessor_account_id(bob())
+            .attached_deposit(1)
+            .build());
+        contract.nft_seize("token#1".to_string(), carol(), None);
+
+        let json_token = contract.nft_token("token#1".to_string()).unwrap();
+        assert_eq!(json_token.owner_id, carol());
+        assert!(contract.nft_lock_of("token#1".to_string()).is_none());
+        assert!(get_logs().iter().any(
+            |log| log.contains("nft_transfer") && log.contains(r#""authorized_id":"bob.near""#)
+        ));
+    }
+
+    #[test]
+    fn test_approved_account_locks() {
+        let mut context = get_context(false);
+        context.attached_deposit(ONE_NEAR);
+
+        testing_env!(context.build());
+
+        let mut contract = init_nft_contract();
+        mint_nft(&mut contract, alice(), "token#1".to_string());
+        contract.nft_approve("token#1".to_string(), bob(), None, None);
+
+        testing_env!(context.predecessor_account_id(bob()).build());
+        contract.nft_lock("token#1".to_string(), bob(), U64(u64::MAX));
+        assert_eq!(
+            contract
+                .nft_lock_of("token#1".to_string())
+                .unwrap()
+                .locker_id,
+            bob()
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Soulbound token cannot be transferred or approved")]
+    fn test_soulbound_cannot_be_locked() {
+        let mut context = get_context(false);
+        context.attached_deposit(ONE_NEAR);
+
+        testing_env!(context.build());
+
+        let mut contract = init_nft_contract();
+        contract.nft_mint(
+            "token#1".to_string(),
+            token_metadata(None),
+            alice(),
+            None,
+            Some(true),
+            None,
+        );
+        contract.nft_lock("token#1".to_string(), bob(), U64(u64::MAX));
+    }
+
+    #[test]
+    fn test_operator_transfer() {
+        let mut context = get_context(false);
+        context.attached_deposit(ONE_NEAR);
+
+        testing_env!(context.build());
+
+        let mut contract = init_nft_contract();
+        mint_nft(&mut contract, alice(), "token#1".to_string());
+        contract.nft_approve_all(bob(), None);
+        let approval_id = FIRST_OPERATOR_APPROVAL_ID;
+        assert!(contract.nft_is_approved("token#1".to_string(), bob(), Some(approval_id)));
+
+        testing_env!(context
+            .predecessor_account_id(bob())
+            .attached_deposit(1)
+            .build());
+        contract.nft_transfer(carol(), "token#1".to_string(), Some(approval_id), None);
+        assert_eq!(
+            contract.nft_token("token#1".to_string()).unwrap().owner_id,
+            carol()
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "The given approval id is differrent from the actual approval id")]
+    fn test_operator_approval_id_differs_from_revoked_token_approval() {
+        let mut context = get_context(false);
+        context.attached_deposit(ONE_NEAR);
+
+        testing_env!(context.build());
+
+        let mut contract = init_nft_contract();
+        mint_nft(&mut contract, alice(), "token#1".to_string());
+        contract.nft_approve("token#1".to_string(), bob(), None, None);
+
+        testing_env!(context.attached_deposit(1).build());
+        contract.nft_revoke("token#1".to_string(), bob());
+
+        testing_env!(context.attached_deposit(ONE_NEAR).build());
+        contract.nft_approve_all(bob(), None);
+        assert!(!contract.nft_is_approved("token#1".to_string(), bob(), Some(0)));
+
+        testing_env!(context
+            .predecessor_account_id(bob())
+            .attached_deposit(1)
+            .build());
+        contract.nft_transfer(carol(), "token#1".to_string(), Some(0), None);
+    }
+
+    #[test]
+    fn test_expired_approval() {
+        let mut context = get_context(false);
+        context.attached_deposit(ONE_NEAR);
+
+        testing_env!(context.build());
+
+        let mut contract = init_nft_contract();
+        mint_nft(&mut contract, alice(), "token#1".to_string());
+        contract.nft_approve("token#1".to_string(), bob(), None, Some(U64(100)));
+        assert!(contract.nft_is_approved("token#1".to_string(), bob(), Some(0)));
+
+        testing_env!(context.block_timestamp(100).build());
+        assert!(!contract.nft_is_approved("token#1".to_string(), bob(), Some(0)));
+
+        contract.nft_prune_expired_approvals("token#1".to_string());
+        let json_token = contract.nft_token("token#1".to_string()).unwrap();
+        assert!(json_token.approved_account_ids.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot add more than 1 approvals to an NFT")]
+    fn test_max_approvals_per_token() {
+        let mut context = get_context(false);
+        context.attached_deposit(ONE_NEAR);
+
+        testing_env!(context.build());
+
+        let mut contract = init_nft_contract();
+        mint_nft(&mut contract, alice(), "token#1".to_string());
+        contract.set_max_approvals_per_token(1);
+
+        contract.nft_approve("token#1".to_string(), bob(), None, None);
+        assert_eq!(
+            contract
+                .nft_approvals("token#1".to_string(), None, None)
+                .len(),
+            1
+        );
+        contract.nft_approve("token#1".to_string(), carol(), None, None);
+    }
+
+    #[test]
+    fn test_tokens_approved_for() {
+        let mut context = get_context(false);
+        context.attached_deposit(ONE_NEAR);
+
+        testing_env!(context.build());
+
+        let mut contract = init_nft_contract();
+        mint_nft(&mut contract, alice(), "token#1".to_string());
+        mint_nft(&mut contract, alice(), "token#2".to_string());
+        contract.nft_approve("token#1".to_string(), bob(), None, None);
+        contract.nft_approve("token#2".to_string(), bob(), None, None);
+        assert_eq!(contract.nft_tokens_approved_for(bob(), None, None).len(), 2);
+
+        testing_env!(context.attached_deposit(1).build());
+        contract.nft_transfer(carol(), "token#1".to_string(), None, None);
+        contract.nft_revoke("token#2".to_string(), bob());
+        assert!(contract
+            .nft_tokens_approved_for(bob(), None, None)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_rejected_approval_rolled_back() {
+        let mut context = get_context(false);
+        context.attached_deposit(ONE_NEAR);
+
+        testing_env!(context.build());
+
+        let mut contract = init_nft_contract();
+        mint_nft(&mut contract, alice(), "token#1".to_string());
+        contract.nft_approve("token#1".to_string(), bob(), Some("list".to_string()), None);
+
+        testing_env!(
+            context.build(),
+            VMConfig::test(),
+            RuntimeFeesConfig::test(),
+            Default::default(),
+            vec![PromiseResult::Failed],
+        );
+        assert!(!contract.nft_resolve_approve("token#1".to_string(), alice(), bob(), 0));
+        assert!(!contract.nft_is_approved("token#1".to_string(), bob(), None));
+        assert!(contract
+            .nft_tokens_approved_for(bob(), None, None)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_rejected_operator_approval_rolled_back() {
+        let mut context = get_context(false);
+        context.attached_deposit(ONE_NEAR);
+
+        testing_env!(context.build());
+
+        let mut contract = init_nft_contract();
+        mint_nft(&mut contract, alice(), "token#1".to_string());
+        contract.nft_approve_all(bob(), Some("list".to_string()));
+
+        testing_env!(
+            context.build(),
+            VMConfig::test(),
+            RuntimeFeesConfig::test(),
+            Default::default(),
+            vec![PromiseResult::Failed],
+        );
+        assert!(!contract.nft_resolve_approve_all(alice(), bob(), FIRST_OPERATOR_APPROVAL_ID));
+        assert!(!contract.nft_is_approved("token#1".to_string(), bob(), None));
+    }
+
+    #[test]
+    fn test_approval_events() {
+        let mut context = get_context(false);
+        context.attached_deposit(ONE_NEAR);
+
+        testing_env!(context.build());
+
+        let mut contract = init_nft_contract();
+        mint_nft(&mut contract, alice(), "token#1".to_string());
+        contract.nft_approve("token#1".to_string(), bob(), None, None);
+        assert_eq!(
+            get_logs().last().unwrap(),
+            r#"EVENT_JSON:{"standard":"nep171","version":"1.0.0","event":"nft_approve","data":[{"owner_id":"alice.near","account_id":"bob.near","approval_id":0,"token_ids":["token#1"]}]}"#
+        );
+
+        testing_env!(context.attached_deposit(1).build());
+        contract.nft_transfer(carol(), "token#1".to_string(), None, None);
+        assert_eq!(
+            get_logs()[0],
+            r#"EVENT_JSON:{"standard":"nep171","version":"1.0.0","event":"nft_revoke_all_accounts","data":[{"owner_id":"alice.near","account_ids":["bob.near"],"token_ids":["token#1"]}]}"#
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "carol.near cannot be approved on this contract")]
+    fn test_approval_allowlist() {
+        let mut context = get_context(false);
+        context.attached_deposit(ONE_NEAR);
+
+        testing_env!(context.build());
+
+        let mut contract = init_nft_contract();
+        mint_nft(&mut contract, alice(), "token#1".to_string());
+        contract.set_approval_filter_mode(ApprovalFilterMode::Allowlist);
+        contract.add_approval_filter_accounts(vec![bob()]);
+
+        contract.nft_approve("token#1".to_string(), bob(), None, None);
+        contract.nft_approve("token#1".to_string(), carol(), None, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Approved accounts must transfer through nft_transfer_payout")]
+    fn test_payout_transfers_only() {
+        let mut context = get_context(false);
+        context.attached_deposit(ONE_NEAR);
+
+        testing_env!(context.build());
+
+        let mut contract = init_nft_contract();
+        mint_nft(&mut contract, alice(), "token#1".to_string());
+        contract.set_payout_transfers_only(true);
+        contract.nft_approve("token#1".to_string(), bob(), None, None);
+
+        testing_env!(context
+            .predecessor_account_id(bob())
+            .attached_deposit(1)
+            .build());
+        contract.nft_transfer(carol(), "token#1".to_string(), Some(0), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "bob.near cannot be approved on this contract")]
+    fn test_blocklisted_approval_cannot_transfer() {
+        let mut context = get_context(false);
+        context.attached_deposit(ONE_NEAR);
+
+        testing_env!(context.build());
+
+        let mut contract = init_nft_contract();
+        mint_nft(&mut contract, alice(), "token#1".to_string());
+        contract.nft_approve("token#1".to_string(), bob(), None, None);
+        contract.set_approval_filter_mode(ApprovalFilterMode::Blocklist);
+        contract.add_approval_filter_accounts(vec![bob()]);
+
+        testing_env!(context
+            .predecessor_account_id(bob())
+            .attached_deposit(1)
+            .build());
+        contract.nft_transfer(carol(), "token#1".to_string(), Some(0), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Approved accounts must transfer through nft_transfer_payout")]
+    fn test_payout_transfers_only_seize() {
+        let mut context = get_context(false);
+        context.attached_deposit(ONE_NEAR);
+
+        testing_env!(context.build());
+
+        let mut contract = init_nft_contract();
+        mint_nft(&mut contract, alice(), "token#1".to_string());
+        contract.set_payout_transfers_only(true);
+        contract.nft_lock("token#1".to_string(), bob(), U64(u64::MAX));
+
+        testing_env!(context
+            .predecessor_account_id(bob())
+            .attached_deposit(1)
+            .build());
+        contract.nft_seize("token#1".to_string(), carol(), None);
+    }
+
+    #[test]
+    fn test_update_royalty_recipient_all() {
+        let mut context = get_context(false);
+        context.attached_deposit(ONE_NEAR);
+
+        testing_env!(context.build());
+
+        let mut contract = init_nft_contract();
+        for token_id in ["token#1", "token#2"] {
+            contract.nft_mint(
+                token_id.to_string(),
+                token_metadata(None),
+                alice(),
+                Some(HashMap::from([(bob(), 500), (carol(), 500)])),
+                None,
+                None,
+            );
+        }
+
+        testing_env!(context.predecessor_account_id(bob()).build());
+        assert_eq!(
+            contract
+                .nft_update_royalty_recipient_all(carol(), None)
+                .updated,
+            2
+        );
+        assert_eq!(
+            contract
+                .nft_update_royalty_recipient_all(carol(), None)
+                .updated,
+            0
+        );
+
+        let json_token = contract.nft_token("token#2".to_string()).unwrap();
+        assert_eq!(json_token.royalty, HashMap::from([(carol(), 1_000)]));
+        assert!(contract
+            .nft_tokens_for_royalty_recipient(bob(), None, None)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_default_royalty() {
+        let mut context = get_context(false);
+        context.attached_deposit(ONE_NEAR);
+
+        testing_env!(context.build());
+
+        let mut contract = NftContract::new(
+            alice(),
+            init_nft_contract().nft_metadata(),
+            None,
+            Some(HashMap::from([(bob(), 1_000)])),
+        );
+        mint_nft(&mut contract, alice(), "token#1".to_string());
+        assert_eq!(
+            contract.nft_royalty("token#1".to_string(), None),
+            HashMap::from([(bob(), 1_000)])
+        );
+        assert_eq!(
+            contract.nft_token("token#1".to_string()).unwrap().royalty,
+            HashMap::from([(bob(), 1_000)])
+        );
+
+        let payout = contract.nft_payout("token#1".to_string(), U128(100), 2);
+        assert_eq!(payout.payout[&bob()], U128(10));
+        assert_eq!(payout.payout[&alice()], U128(90));
+    }
+
+    #[test]
+    fn test_empty_royalty_opts_out_of_default() {
+        let mut context = get_context(false);
+        context.attached_deposit(ONE_NEAR);
+
+        testing_env!(context.build());
+
+        let mut contract = NftContract::new(
+            alice(),
+            init_nft_contract().nft_metadata(),
+            None,
+            Some(HashMap::from([(bob(), 1_000)])),
+        );
+        contract.nft_mint(
+            "token#1".to_string(),
+            token_metadata(None),
+            alice(),
+            Some(HashMap::new()),
+            None,
+            None,
+        );
+
+        assert!(contract.nft_royalty("token#1".to_string(), None).is_empty());
+        let payout = contract.nft_payout("token#1".to_string(), U128(100), 1);
+        assert_eq!(payout.payout[&alice()], U128(100));
+    }
+
+    #[test]
+    fn test_update_default_royalty_recipient() {
+        let mut context = get_context(false);
+        context.attached_deposit(ONE_NEAR);
+
+        testing_env!(context.build());
+
+        let mut contract = NftContract::new(
+            alice(),
+            init_nft_contract().nft_metadata(),
+            None,
+            Some(HashMap::from([(bob(), 1_000)])),
+        );
+        mint_nft(&mut contract, alice(), "token#1".to_string());
+
+        testing_env!(context.predecessor_account_id(bob()).build());
+        contract.nft_update_royalty_recipient_all(carol(), None);
+
+        assert_eq!(
+            contract.nft_royalty("token#1".to_string(), None),
+            HashMap::from([(carol(), 1_000)])
+        );
+        assert!(get_logs()
+            .iter()
+            .any(|log| log.contains(r#""event":"nft_royalty_update""#)));
+    }
+
+    #[test]
+    fn test_update_royalty_recipient_all_skips_scheduled_merges() {
+        let mut context = get_context(false);
+        context.attached_deposit(ONE_NEAR);
+
+        testing_env!(context.build());
+
+        let mut contract = init_nft_contract();
+        contract.nft_mint(
+            "token#1".to_string(),
+            token_metadata(None),
+            alice(),
+            Some(HashMap::from([(bob(), 500), (carol(), 500)])),
+            None,
+            Some(HashMap::from([(
+                bob(),
+                RoyaltySchedule::Steps(vec![RoyaltyStep {
+                    from: U64(150),
+                    royalty: 0,
+                }]),
+            )])),
+        );
+        contract.nft_mint(
+            "token#2".to_string(),
+            token_metadata(None),
+            alice(),
+            Some(HashMap::from([(bob(), 500)])),
+            None,
+            None,
+        );
+
+        testing_env!(context.predecessor_account_id(bob()).build());
+        let update = contract.nft_update_royalty_recipient_all(carol(), Some(1));
+        assert_eq!(update.updated, 1);
+        assert_eq!(update.skipped_token_ids, vec!["token#1".to_string()]);
+
+        let update = contract.nft_update_royalty_recipient_all(carol(), Some(1));
+        assert_eq!(update.updated, 0);
+        assert_eq!(
+            contract.nft_token("token#2".to_string()).unwrap().royalty,
+            HashMap::from([(carol(), 500)])
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Default royalty is frozen")]
+    fn test_frozen_default_royalty() {
+        let mut context = get_context(false);
+        context.attached_deposit(ONE_NEAR);
+
+        testing_env!(context.build());
+
+        let mut contract = init_nft_contract();
+        contract.set_default_royalty(HashMap::from([(bob(), 1_000)]));
+        contract.freeze_default_royalty();
+        contract.set_default_royalty(HashMap::default());
+    }
+
+    #[test]
+    fn test_ft_payout() {
+        let mut context = get_context(false);
+        context.attached_deposit(ONE_NEAR);
+
+        testing_env!(context.build());
+
+        let usdc: AccountId = "usdc.near".parse().unwrap();
+        let mut contract = init_nft_contract();
+        contract.nft_mint(
+            "token#1".to_string(),
+            token_metadata(None),
+            alice(),
+            Some(HashMap::from([(bob(), 1_000)])),
+            None,
+            None,
+        );
+        contract.add_ft_payout_contract(usdc.clone());
+
+        testing_env!(context.predecessor_account_id(bob()).build());
+        contract.nft_set_ft_royalty("token#1".to_string(), usdc.clone(), Some(500));
+
+        let payout = contract.nft_payout_ft("token#1".to_string(), U128(100), 2, usdc.clone());
+        assert_eq!(payout.ft_contract_id, Some(usdc));
+        assert_eq!(payout.payout[&bob()], U128(5));
+
+        let payout = contract.nft_payout("token#1".to_string(), U128(100), 2);
+        assert_eq!(payout.payout[&bob()], U128(10));
+    }
+
+    #[test]
+    #[should_panic(expected = "Predecessor should be a royalty recipient of NFT")]
+    fn test_ft_royalty_set_by_non_recipient() {
+        let mut context = get_context(false);
+        context.attached_deposit(ONE_NEAR);
+
+        testing_env!(context.build());
+
+        let usdc: AccountId = "usdc.near".parse().unwrap();
+        let mut contract = init_nft_contract();
+        contract.nft_mint(
+            "token#1".to_string(),
+            token_metadata(None),
+            alice(),
+            Some(HashMap::from([(bob(), 1_000)])),
+            None,
+            None,
+        );
+        contract.add_ft_payout_contract(usdc.clone());
+
+        contract.nft_set_ft_royalty("token#1".to_string(), usdc, Some(5_000));
+    }
+
+    #[test]
+    #[should_panic(expected = "Royalty override can't exceed the recipient's share of 1000")]
+    fn test_ft_royalty_cannot_raise_share() {
+        let mut context = get_context(false);
+        context.attached_deposit(ONE_NEAR);
+
+        testing_env!(context.build());
+
+        let usdc: AccountId = "usdc.near".parse().unwrap();
+        let mut contract = init_nft_contract();
+        contract.nft_mint(
+            "token#1".to_string(),
+            token_metadata(None),
+            alice(),
+            Some(HashMap::from([(bob(), 1_000)])),
+            None,
+            None,
+        );
+        contract.add_ft_payout_contract(usdc.clone());
+
+        testing_env!(context.predecessor_account_id(bob()).build());
+        contract.nft_set_ft_royalty("token#1".to_string(), usdc, Some(1_001));
+    }
+
+    #[test]
+    fn test_royalty_schedule() {
+        let mut context = get_context(false);
+        context.attached_deposit(ONE_NEAR);
+
+        testing_env!(context.build());
+
+        let mut contract = init_nft_contract();
+        contract.nft_mint(
+            "token#1".to_string(),
+            token_metadata(None),
+            alice(),
+            Some(HashMap::from([(bob(), 1_000), (carol(), 500)])),
+            None,
+            Some(HashMap::from([
+                (
+                    bob(),
+                    RoyaltySchedule::LinearDecay {
+                        start: U64(100),
+                        end: U64(200),
+                        floor: 200,
+                    },
+                ),
+                (
+                    carol(),
+                    RoyaltySchedule::Steps(vec![RoyaltyStep {
+                        from: U64(150),
+                        royalty: 0,
+                    }]),
+                ),
+            ])),
+        );
+
+        context.block_timestamp(150);
+        testing_env!(context.build());
+        assert_eq!(
+            contract.nft_royalty("token#1".to_string(), None),
+            HashMap::from([(bob(), 600)])
+        );
+
+        context.block_timestamp(300);
+        testing_env!(context.build());
+        let payout = contract.nft_payout("token#1".to_string(), U128(10_000), 2);
+        assert_eq!(payout.payout[&bob()], U128(200));
+        assert_eq!(payout.payout[&alice()], U128(9_800));
+    }
+
+    #[test]
+    fn test_buy_listing() {
+        let mut context = get_context(false);
+        context.attached_deposit(ONE_NEAR);
+
+        testing_env!(context.build());
+
+        let mut contract = init_nft_contract();
+        mint_nft(&mut contract, alice(), "token#1".to_string());
+        contract.nft_list("token#1".to_string(), U128(ONE_NEAR), None);
+        assert_eq!(contract.nft_listings(None, None).len(), 1);
+
+        testing_env!(context
+            .predecessor_account_id(bob())
+            .attached_deposit(2 * ONE_NEAR)
+            .build());
+        contract.nft_buy("token#1".to_string());
+
+        assert_eq!(
+            contract.nft_token("token#1".to_string()).unwrap().owner_id,
+            bob()
+        );
+        assert!(contract.nft_listing("token#1".to_string()).is_none());
+        assert!(get_logs()
+            .iter()
+            .any(|log| log.contains(r#""event":"nft_sale""#)));
+    }
+
+    #[test]
+    fn test_listing_removed_on_transfer() {
+        let mut context = get_context(false);
+        context.attached_deposit(ONE_NEAR);
+
+        testing_env!(context.build());
+
+        let mut contract = init_nft_contract();
+        mint_nft(&mut contract, alice(), "token#1".to_string());
+        contract.nft_list("token#1".to_string(), U128(ONE_NEAR), None);
+
+        testing_env!(context.attached_deposit(1).build());
+        contract.nft_transfer(bob(), "token#1".to_string(), None, None);
+
+        assert!(contract.nft_listing("token#1".to_string()).is_none());
+        assert!(get_logs()
+            .iter()
+            .any(|log| log.contains(r#""event":"nft_delist""#)));
+    }
+
+    #[test]
+    fn test_english_auction() {
+        let mut context = get_context(false);
+        context.attached_deposit(ONE_NEAR);
+
+        testing_env!(context.build());
+
+        let mut contract = init_nft_contract();
+        mint_nft(&mut contract, alice(), "token#1".to_string());
+        contract.nft_start_auction(
+            "token#1".to_string(),
+            U128(ONE_NEAR),
+            U64(AUCTION_EXTENSION_WINDOW * 2),
+        );
+        assert!(contract.nft_lock_of("token#1".to_string()).is_some());
+
+        testing_env!(context.predecessor_account_id(bob()).build());
+        contract.nft_bid("token#1".to_string());
+
+        // a late bid pushes the end back
+        testing_env!(context
+            .predecessor_account_id(carol())
+            .attached_deposit(2 * ONE_NEAR)
+            .block_timestamp(AUCTION_EXTENSION_WINDOW * 2 - 1)
+            .build());
+        contract.nft_bid("token#1".to_string());
+        let ends_at = contract.nft_auction("token#1".to_string()).unwrap().ends_at;
+        assert_eq!(ends_at.0, AUCTION_EXTENSION_WINDOW * 3 - 1);
+
+        testing_env!(context.block_timestamp(ends_at.0).build());
+        contract.nft_settle_auction("token#1".to_string());
+
+        assert_eq!(
+            contract.nft_token("token#1".to_string()).unwrap().owner_id,
+            carol()
+        );
+        assert!(contract.nft_lock_of("token#1".to_string()).is_none());
+        assert!(contract.nft_auction("token#1".to_string()).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "Token ids containing ':' are reserved")]
+    fn test_mint_reserved_token_id() {
+        let mut context = get_context(false);
+        context.attached_deposit(ONE_NEAR);
+
+        testing_env!(context.build());
+
+        let mut contract = init_nft_contract();
+        mint_nft(&mut contract, alice(), "dutch:0".to_string());
+    }
+
+    #[test]
+    fn test_dutch_auction_rebate() {
+        let mut context = get_context(false);
+        testing_env!(context.build());
+
+        let mut contract = init_nft_contract();
+        contract.set_dutch_auction(DutchAuctionConfig {
+            start_price: U128(4 * ONE_NEAR),
+            floor_price: U128(ONE_NEAR),
+            price_step: U128(ONE_NEAR),
+            step_duration: U64(100),
+            start_at: U64(0),
+            ends_at: U64(1_000),
+            max_supply: 2,
+            metadata: token_metadata(None),
+            rebate: true,
+        });
+
+        testing_env!(context
+            .predecessor_account_id(bob())
+            .attached_deposit(5 * ONE_NEAR)
+            .build());
+        assert_eq!(contract.nft_dutch_mint(), "dutch:0".to_string());
+
+        testing_env!(context
+            .predecessor_account_id(carol())
+            .attached_deposit(3 * ONE_NEAR)
+            .block_timestamp(250)
+            .build());
+        assert_eq!(contract.nft_dutch_price(), U128(2 * ONE_NEAR));
+        contract.nft_dutch_mint();
+
+        // sold out, the clearing price is the last one paid
+        assert_eq!(contract.nft_dutch_rebate_of(bob()), U128(2 * ONE_NEAR));
+        assert_eq!(contract.nft_dutch_rebate_of(carol()), U128(0));
+
+        testing_env!(context.predecessor_account_id(bob()).build());
+        assert_eq!(contract.nft_claim_rebate(), U128(2 * ONE_NEAR));
+    }
+
+    #[test]
+    fn test_dutch_mint_takes_reveal_slot() {
+        let mut context = get_context(false);
+        testing_env!(context.build());
+
+        let mut contract = NftContract::new(
+            alice(),
+            init_nft_contract().nft_metadata(),
+            Some(DelayedRevealConfig {
+                provenance_hash: vec![0; 32].into(),
+                placeholder: token_metadata(Some("hidden".to_string())),
+                supply: 1,
+            }),
+            None,
+        );
+        contract.set_dutch_auction(DutchAuctionConfig {
+            start_price: U128(ONE_NEAR),
+            floor_price: U128(ONE_NEAR),
+            price_step: U128(0),
+            step_duration: U64(100),
+            start_at: U64(0),
+            ends_at: U64(1_000),
+            max_supply: 2,
+            metadata: token_metadata(Some("hidden".to_string())),
+            rebate: false,
+        });
+
+        testing_env!(context
+            .predecessor_account_id(bob())
+            .attached_deposit(2 * ONE_NEAR)
+            .build());
+        let token_id = contract.nft_dutch_mint();
+        let json_token = contract.nft_token(token_id).unwrap();
+        assert_eq!(json_token.metadata.title, Some("hidden".to_string()));
+        assert_eq!(contract.nft_reveal_info().unwrap().minted_count, 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Drop is sold out")]
+    fn test_dutch_mint_reveal_supply() {
+        let mut context = get_context(false);
+        testing_env!(context.build());
+
+        let mut contract = NftContract::new(
+            alice(),
+            init_nft_contract().nft_metadata(),
+            Some(DelayedRevealConfig {
+                provenance_hash: vec![0; 32].into(),
+                placeholder: token_metadata(None),
+                supply: 1,
+            }),
+            None,
+        );
+        contract.set_dutch_auction(DutchAuctionConfig {
+            start_price: U128(ONE_NEAR),
+            floor_price: U128(ONE_NEAR),
+            price_step: U128(0),
+            step_duration: U64(100),
+            start_at: U64(0),
+            ends_at: U64(1_000),
+            max_supply: 2,
+            metadata: token_metadata(None),
+            rebate: false,
+        });
+
+        testing_env!(context
+            .predecessor_account_id(bob())
+            .attached_deposit(2 * ONE_NEAR)
+            .build());
+        contract.nft_dutch_mint();
+        contract.nft_dutch_mint();
+    }
+
+    #[test]
+    fn test_accept_collection_offer() {
+        let mut context = get_context(false);
+        context.attached_deposit(ONE_NEAR);
+
+        testing_env!(context.build());
+
+        let mut contract = init_nft_contract();
+        mint_nft(&mut contract, alice(), "token#1".to_string());
+
+        testing_env!(context.predecessor_account_id(bob()).build());
+        let offer_id = contract.nft_make_offer(None, U64(100));
+        assert_eq!(contract.nft_collection_offers(None, None).len(), 1);
+        assert_eq!(contract.nft_offers_by_bidder(bob(), None, None).len(), 1);
+
+        testing_env!(context
+            .predecessor_account_id(alice())
+            .attached_deposit(1)
+            .build());
+        contract.nft_accept_offer(offer_id, "token#1".to_string());
+
+        assert_eq!(
+            contract.nft_token("token#1".to_string()).unwrap().owner_id,
+            bob()
+        );
+        assert!(contract.nft_offer(offer_id).is_none());
+        assert!(contract.nft_offers_by_bidder(bob(), None, None).is_empty());
+    }
+
+    #[test]
+    fn test_burn_cancels_offers() {
+        let mut context = get_context(false);
+        context.attached_deposit(ONE_NEAR);
+
+        testing_env!(context.build());
+
+        let mut contract = init_nft_contract();
+        mint_nft(&mut contract, alice(), "token#1".to_string());
+
+        testing_env!(context.predecessor_account_id(bob()).build());
+        let offer_id = contract.nft_make_offer(Some("token#1".to_string()), U64(100));
+
+        testing_env!(context
+            .predecessor_account_id(alice())
+            .attached_deposit(1)
+            .build());
+        contract.nft_burn("token#1".to_string(), None);
+
+        assert!(contract.nft_offer(offer_id).is_none());
+        assert!(contract
+            .nft_offers_for_token("token#1".to_string(), None, None)
+            .is_empty());
+        assert!(contract.nft_offers_by_bidder(bob(), None, None).is_empty());
+    }
+
+    #[test]
+    fn test_reject_offer_frees_slot() {
+        let mut context = get_context(false);
+        context.attached_deposit(ONE_NEAR);
+
+        testing_env!(context.build());
+
+        let mut contract = init_nft_contract();
+        mint_nft(&mut contract, alice(), "token#1".to_string());
+
+        testing_env!(context
+            .predecessor_account_id(bob())
+            .attached_deposit(ONE_NEAR / 100)
+            .build());
+        let offer_ids: Vec<U64> = (0..MAX_OFFERS_PER_TOKEN)
+            .map(|_| contract.nft_make_offer(Some("token#1".to_string()), U64(u64::MAX)))
+            .collect();
+
+        testing_env!(context
+            .predecessor_account_id(alice())
+            .attached_deposit(1)
+            .build());
+        contract.nft_reject_offer(offer_ids[0]);
+        assert!(contract.nft_offer(offer_ids[0]).is_none());
+
+        testing_env!(context
+            .predecessor_account_id(carol())
+            .attached_deposit(ONE_NEAR)
+            .build());
+        contract.nft_make_offer(Some("token#1".to_string()), U64(100));
+        assert_eq!(
+            contract
+                .nft_offers_for_token("token#1".to_string(), None, None)
+                .len() as u64,
+            MAX_OFFERS_PER_TOKEN
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Predecessor should be the owner of NFT")]
+    fn test_reject_offer_by_non_owner() {
+        let mut context = get_context(false);
+        context.attached_deposit(ONE_NEAR);
+
+        testing_env!(context.build());
+
+        let mut contract = init_nft_contract();
+        mint_nft(&mut contract, alice(), "token#1".to_string());
+
+        testing_env!(context.predecessor_account_id(bob()).build());
+        let offer_id = contract.nft_make_offer(Some("token#1".to_string()), U64(100));
+
+        testing_env!(context
+            .predecessor_account_id(carol())
+            .attached_deposit(1)
+            .build());
+        contract.nft_reject_offer(offer_id);
+    }
+
+    #[test]
+    fn test_accept_swap() {
+        let mut context = get_context(false);
+        context.attached_deposit(ONE_NEAR);
+
+        testing_env!(context.build());
+
+        let mut contract = init_nft_contract();
+        mint_nft(&mut contract, alice(), "token#1".to_string());
+        mint_nft(&mut contract, bob(), "token#2".to_string());
+
+        let swap_id = contract.nft_propose_swap(
+            vec!["token#1".to_string()],
+            vec!["token#2".to_string()],
+            U64(100),
+        );
+        assert!(contract.nft_lock_of("token#1".to_string()).is_some());
+
+        testing_env!(context
+            .predecessor_account_id(bob())
+            .attached_deposit(1)
+            .build());
+        contract.nft_accept_swap(swap_id);
+
+        assert_eq!(
+            contract.nft_token("token#1".to_string()).unwrap().owner_id,
+            bob()
+        );
+        assert_eq!(
+            contract.nft_token("token#2".to_string()).unwrap().owner_id,
+            alice()
+        );
+        assert!(contract.nft_lock_of("token#1".to_string()).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "Token is locked")]
+    fn test_swap_blocks_approvals() {
+        let mut context = get_context(false);
+        context.attached_deposit(ONE_NEAR);
+
+        testing_env!(context.build());
+
+        let mut contract = init_nft_contract();
+        mint_nft(&mut contract, alice(), "token#1".to_string());
+        mint_nft(&mut contract, bob(), "token#2".to_string());
+
+        contract.nft_propose_swap(
+            vec!["token#1".to_string()],
+            vec!["token#2".to_string()],
+            U64(100),
+        );
+        contract.nft_approve("token#1".to_string(), carol(), None, None);
+    }
+
+    #[test]
+    fn test_wrap_foreign_nft() {
+        let mut context = get_context(false);
+        testing_env!(context.build());
+
+        let foreign: AccountId = "foreign.near".parse().unwrap();
+        let mut contract = init_nft_contract();
+        contract.add_wrap_contract(foreign.clone());
+
+        testing_env!(context.predecessor_account_id(carol()).build());
+        assert!(matches!(
+            contract.nft_on_transfer(bob(), bob(), "1".to_string(), "".to_string()),
+            PromiseOrValue::Value(true)
+        ));
+
+        testing_env!(context.predecessor_account_id(foreign.clone()).build());
+        assert!(matches!(
+            contract.nft_on_transfer(bob(), bob(), "1".to_string(), "".to_string()),
+            PromiseOrValue::Value(false)
+        ));
+
+        let wrapped_id = "wrap:foreign.near:1".to_string();
+        let wrapped = contract.nft_token(wrapped_id.clone()).unwrap();
+        assert_eq!(wrapped.owner_id, bob());
+        assert_eq!(
+            wrapped.metadata.extra,
+            Some(r#"{"contract_id":"foreign.near","token_id":"1"}"#.to_string())
+        );
+
+        testing_env!(context
+            .predecessor_account_id(bob())
+            .attached_deposit(1)
+            .build());
+        contract.nft_burn(wrapped_id.clone(), None);
+        assert!(contract.nft_wrapped_token(wrapped_id).is_none());
+    }
+
+    #[test]
+    fn test_bundle_and_unbundle() {
+        let mut context = get_context(false);
+        context.attached_deposit(ONE_NEAR);
+
+        testing_env!(context.build());
+
+        let mut contract = init_nft_contract();
+        mint_nft(&mut contract, bob(), "token#1".to_string());
+        mint_nft(&mut contract, bob(), "token#2".to_string());
+
+        testing_env!(context.predecessor_account_id(bob()).build());
+        let token_ids = vec!["token#1".to_string(), "token#2".to_string()];
+        let bundle_id = contract.nft_bundle(token_ids.clone(), None);
+        assert_eq!(
+            contract.nft_bundle_components(bundle_id.clone()),
+            Some(token_ids)
+        );
+        // components are held by the contract account
+        assert_eq!(
+            contract.nft_token("token#1".to_string()).unwrap().owner_id,
+            alice()
+        );
+
+        testing_env!(context.attached_deposit(1).build());
+        contract.nft_transfer(carol(), bundle_id.clone(), None, None);
+
+        testing_env!(context.predecessor_account_id(carol()).build());
+        contract.nft_unbundle(bundle_id.clone());
+
+        assert!(contract.nft_token(bundle_id).is_none());
+        assert_eq!(
+            contract.nft_token("token#2".to_string()).unwrap().owner_id,
+            carol()
+        );
+        assert!(contract.nft_bundle_of("token#2".to_string()).is_none());
+    }
+
+    #[test]
+    fn test_approved_account_bundles() {
+        let mut context = get_context(false);
+        context.attached_deposit(ONE_NEAR);
+
+        testing_env!(context.build());
+
+        let mut contract = init_nft_contract();
+        mint_nft(&mut contract, bob(), "token#1".to_string());
+        mint_nft(&mut contract, bob(), "token#2".to_string());
+
+        testing_env!(context.predecessor_account_id(bob()).build());
+        contract.nft_approve_all(carol(), None);
+
+        testing_env!(context.predecessor_account_id(carol()).build());
+        let approval_id = FIRST_OPERATOR_APPROVAL_ID;
+        let bundle_id = contract.nft_bundle(
+            vec!["token#1".to_string(), "token#2".to_string()],
+            Some(vec![approval_id, approval_id]),
+        );
+        assert_eq!(contract.nft_token(bundle_id).unwrap().owner_id, carol());
+    }
+
+    #[test]
+    #[should_panic(expected = "Must give one approval id per NFT")]
+    fn test_bundle_approval_ids_length() {
+        let mut context = get_context(false);
+        context.attached_deposit(ONE_NEAR);
+
+        testing_env!(context.build());
+
+        let mut contract = init_nft_contract();
+        mint_nft(&mut contract, bob(), "token#1".to_string());
+        mint_nft(&mut contract, bob(), "token#2".to_string());
+
+        testing_env!(context.predecessor_account_id(bob()).build());
+        contract.nft_approve_all(carol(), None);
+
+        testing_env!(context.predecessor_account_id(carol()).build());
+        contract.nft_bundle(
+            vec!["token#1".to_string(), "token#2".to_string()],
+            Some(vec![FIRST_OPERATOR_APPROVAL_ID]),
+        );
+    }
+
+    #[test]
+    fn test_nested_transfer() {
+        let mut context = get_context(false);
+        context.attached_deposit(ONE_NEAR);
+
+        testing_env!(context.build());
+
+        let mut contract = init_nft_contract();
+        for token_id in ["character", "sword", "gem"] {
+            mint_nft(&mut contract, bob(), token_id.to_string());
+        }
+
+        testing_env!(context.predecessor_account_id(bob()).build());
+        contract.nft_attach("sword".to_string(), "character".to_string());
+        contract.nft_attach("gem".to_string(), "sword".to_string());
+        assert_eq!(
+            contract.nft_parent_of("gem".to_string()),
+            Some("sword".to_string())
+        );
+
+        testing_env!(context.attached_deposit(1).build());
+        contract.nft_transfer(carol(), "character".to_string(), None, None);
+
+        assert_eq!(
+            contract.nft_token("gem".to_string()).unwrap().owner_id,
+            carol()
+        );
+        assert_eq!(contract.nft_root_owner("gem".to_string()), carol());
+        let tree = contract.nft_ownership_tree("character".to_string());
+        assert_eq!(tree.children[0].children[0].token_id, "gem".to_string());
+
+        testing_env!(context.predecessor_account_id(carol()).build());
+        contract.nft_detach("sword".to_string());
+        assert!(contract.nft_children_of("character".to_string()).is_empty());
+        assert_eq!(
+            contract.nft_children_of("sword".to_string()),
+            vec!["gem".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_attach_clears_approvals() {
+        let mut context = get_context(false);
+        context.attached_deposit(ONE_NEAR);
+
+        testing_env!(context.build());
+
+        let mut contract = init_nft_contract();
+        for token_id in ["character", "sword"] {
+            mint_nft(&mut contract, bob(), token_id.to_string());
+        }
+
+        testing_env!(context.predecessor_account_id(bob()).build());
+        contract.nft_approve("sword".to_string(), carol(), None, None);
+        contract.nft_attach("sword".to_string(), "character".to_string());
+
+        assert!(!contract.nft_is_approved("sword".to_string(), carol(), None));
+        assert!(contract
+            .nft_tokens_approved_for(carol(), None, None)
+            .is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "Token is locked")]
+    fn test_attach_to_locked_parent() {
+        let mut context = get_context(false);
+        context.attached_deposit(ONE_NEAR);
+
+        testing_env!(context.build());
+
+        let mut contract = init_nft_contract();
+        for token_id in ["character", "sword"] {
+            mint_nft(&mut contract, bob(), token_id.to_string());
+        }
+
+        testing_env!(context.predecessor_account_id(bob()).build());
+        contract.nft_lock("character".to_string(), carol(), U64(u64::MAX));
+        contract.nft_attach("sword".to_string(), "character".to_string());
+    }
+
+    #[test]
+    #[should_panic(expected = "Token is locked")]
+    fn test_detach_from_locked_tree() {
+        let mut context = get_context(false);
+        context.attached_deposit(ONE_NEAR);
+
+        testing_env!(context.build());
+
+        let mut contract = init_nft_contract();
+        for token_id in ["character", "sword", "gem"] {
+            mint_nft(&mut contract, bob(), token_id.to_string());
+        }
+
+        testing_env!(context.predecessor_account_id(bob()).build());
+        contract.nft_attach("sword".to_string(), "character".to_string());
+        contract.nft_attach("gem".to_string(), "sword".to_string());
+        contract.nft_lock("character".to_string(), carol(), U64(u64::MAX));
+
+        testing_env!(context.attached_deposit(1).build());
+        contract.nft_detach("gem".to_string());
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot attach more than 10 tokens to a parent")]
+    fn test_attach_too_many_children() {
+        let mut context = get_context(false);
+        // keep the refunds of every mint and attach within the account balance
+        context.attached_deposit(ONE_NEAR / 100);
+
+        testing_env!(context.build());
+
+        let mut contract = init_nft_contract();
+        mint_nft(&mut contract, bob(), "character".to_string());
+        for i in 0..=MAX_CHILDREN_PER_TOKEN {
+            mint_nft(&mut contract, bob(), format!("item#{}", i));
+        }
+
+        testing_env!(context.predecessor_account_id(bob()).build());
+        for i in 0..=MAX_CHILDREN_PER_TOKEN {
+            contract.nft_attach(format!("item#{}", i), "character".to_string());
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Token is attached to a parent")]
+    fn test_attached_transfer() {
+        let mut context = get_context(false);
+        context.attached_deposit(ONE_NEAR);
+
+        testing_env!(context.build());
+
+        let mut contract = init_nft_contract();
+        mint_nft(&mut contract, bob(), "character".to_string());
+        mint_nft(&mut contract, bob(), "sword".to_string());
+
+        testing_env!(context.predecessor_account_id(bob()).build());
+        contract.nft_attach("sword".to_string(), "character".to_string());
+
+        testing_env!(context.attached_deposit(1).build());
+        contract.nft_transfer(carol(), "sword".to_string(), None, None);
+    }
+
+    #[test]
+    fn test_craft() {
+        let mut context = get_context(false);
+        context.attached_deposit(ONE_NEAR);
+
+        testing_env!(context.build());
+
+        let mut contract = init_nft_contract();
+        contract.nft_create_series("ore".to_string(), token_metadata(None));
+        contract.nft_create_series("sword".to_string(), token_metadata(None));
+        let ore_1 = contract.nft_mint_series("ore".to_string(), bob());
+        let ore_2 = contract.nft_mint_series("ore".to_string(), bob());
+        let recipe_id = contract.nft_create_recipe(
+            vec![RecipeInput {
+                series_id: Some("ore".to_string()),
+                attributes: HashMap::new(),
+                quantity: 2,
+            }],
+            "sword".to_string(),
+            Some(1),
+        );
+
+        testing_env!(context.predecessor_account_id(bob()).build());
+        let sword = contract.nft_craft(recipe_id, vec![ore_1.clone(), ore_2]);
+
+        assert!(contract.nft_token(ore_1).is_none());
+        assert_eq!(contract.nft_token(sword.clone()).unwrap().owner_id, bob());
+        assert_eq!(contract.nft_series_of(sword), Some("sword".to_string()));
+        assert_eq!(contract.nft_recipe(recipe_id).unwrap().crafted_count, 1);
+    }
+
+    #[test]
+    fn test_craft_with_trait_input() {
+        let mut context = get_context(false);
+        context.attached_deposit(ONE_NEAR);
+
+        testing_env!(context.build());
+
+        let game: AccountId = "game.near".parse().unwrap();
+        let mut contract = init_nft_contract();
+        contract.nft_create_series("sword".to_string(), token_metadata(None));
+        mint_nft(&mut contract, bob(), "gem".to_string());
+        contract.add_game_contract(game.clone());
+        let recipe_id = contract.nft_create_recipe(
+            vec![RecipeInput {
+                series_id: None,
+                attributes: HashMap::from([("rarity".to_string(), AttributeValue::Number(5))]),
+                quantity: 1,
+            }],
+            "sword".to_string(),
+            None,
+        );
+
+        testing_env!(context.predecessor_account_id(game).build());
+        contract.nft_set_attributes(
+            "gem".to_string(),
+            HashMap::from([("rarity".to_string(), AttributeValue::Number(5))]),
+        );
+
+        testing_env!(context.predecessor_account_id(bob()).build());
+        let sword = contract.nft_craft(recipe_id, vec!["gem".to_string()]);
+        assert_eq!(contract.nft_series_of(sword), Some("sword".to_string()));
+    }
+
+    #[test]
+    #[should_panic(expected = "Duplicate token ids")]
+    fn test_craft_duplicate_tokens() {
+        let mut context = get_context(false);
+        context.attached_deposit(ONE_NEAR);
+
+        testing_env!(context.build());
+
+        let mut contract = init_nft_contract();
+        contract.nft_create_series("ore".to_string(), token_metadata(None));
+        contract.nft_create_series("sword".to_string(), token_metadata(None));
+        let ore = contract.nft_mint_series("ore".to_string(), bob());
+        let recipe_id = contract.nft_create_recipe(
+            vec![RecipeInput {
+                series_id: Some("ore".to_string()),
+                attributes: HashMap::new(),
+                quantity: 2,
+            }],
+            "sword".to_string(),
+            None,
+        );
+
+        testing_env!(context.predecessor_account_id(bob()).build());
+        contract.nft_craft(recipe_id, vec![ore.clone(), ore]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Bundles must be unbundled instead")]
+    fn test_craft_with_bundle() {
+        let mut context = get_context(false);
+        context.attached_deposit(ONE_NEAR);
+
+        testing_env!(context.build());
+
+        let game: AccountId = "game.near".parse().unwrap();
+        let mut contract = init_nft_contract();
+        contract.add_game_contract(game.clone());
+        contract.nft_create_series("sword".to_string(), token_metadata(None));
+        mint_nft(&mut contract, bob(), "token#1".to_string());
+        mint_nft(&mut contract, bob(), "token#2".to_string());
+        let recipe_id = contract.nft_create_recipe(
+            vec![RecipeInput {
+                series_id: None,
+                attributes: HashMap::from([("rarity".to_string(), AttributeValue::Number(5))]),
+                quantity: 1,
+            }],
+            "sword".to_string(),
+            None,
+        );
+
+        testing_env!(context.predecessor_account_id(bob()).build());
+        let bundle_id =
+            contract.nft_bundle(vec!["token#1".to_string(), "token#2".to_string()], None);
+
+        testing_env!(context.predecessor_account_id(game).build());
+        contract.nft_set_attributes(
+            bundle_id.clone(),
+            HashMap::from([("rarity".to_string(), AttributeValue::Number(5))]),
+        );
+
+        testing_env!(context.predecessor_account_id(bob()).build());
+        contract.nft_craft(recipe_id, vec![bundle_id]);
+    }
+
+    #[test]
+    fn test_level_up() {
+        let mut context = get_context(false);
+        context.attached_deposit(ONE_NEAR);
+
+        testing_env!(context.build());
+
+        let game: AccountId = "game.near".parse().unwrap();
+        let mut contract = init_nft_contract();
+        mint_nft(&mut contract, bob(), "token#1".to_string());
+        contract.add_game_contract(game.clone());
+
+        testing_env!(context.predecessor_account_id(game).build());
+        contract.nft_set_attributes(
+            "token#1".to_string(),
+            HashMap::from([
+                (
+                    "class".to_string(),
+                    AttributeValue::String("mage".to_string()),
+                ),
+                ("level".to_string(), AttributeValue::Number(1)),
+            ]),
+        );
+        assert_eq!(
+            contract.nft_increment_attribute("token#1".to_string(), "level".to_string(), 1),
+            2
+        );
+
+        let token = contract.nft_token("token#1".to_string()).unwrap();
+        assert_eq!(token.attributes["level"], AttributeValue::Number(2));
+        assert_eq!(
+            contract
+                .nft_tokens_by_trait("level".to_string(), AttributeValue::Number(2), None, None)
+                .len(),
+            1
+        );
+        assert!(contract
+            .nft_tokens_by_trait("level".to_string(), AttributeValue::Number(1), None, None)
+            .is_empty());
+        assert!(get_logs()
+            .iter()
+            .any(|log| log.contains(r#""event":"nft_attributes_update""#)));
+    }
 }
@@ -0,0 +1,151 @@
+use crate::*;
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TokenLock {
+    pub locker_id: AccountId,
+    /// block timestamp in nanoseconds at which the lock is released by itself
+    pub until: U64,
+    /// the swap proposal holding the lock, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub swap_id: Option<U64>,
+}
+
+#[near_bindgen]
+impl NftContract {
+    /// Lock the NFT in place for `locker_id`, blocking transfers, burns and new approvals.
+    #[payable]
+    pub fn nft_lock(&mut self, token_id: TokenId, locker_id: AccountId, until: U64) {
+        assert_at_least_one_yocto();
+        let initial_storage_usage = env::storage_usage();
+
+        let token = self
+            .tokens_by_id
+            .get(&token_id)
+            .expect("Token doesn't exist");
+
+        let predecessor_id = env::predecessor_account_id();
+        assert!(
+            self.internal_is_owner_or_approved(&token_id, &token, &predecessor_id),
+            "Predecessor should be the owner or approved for NFT"
+        );
+        if predecessor_id != token.owner_id {
+            self.assert_approvable(&predecessor_id);
+        }
+        // a locker must be able to seize the token
+        self.assert_not_soulbound(&token_id);
+        assert!(
+            env::block_timestamp() < until.0,
+            "Lock must end in the future"
+        );
+        self.assert_not_locked(&token_id);
+        self.assert_not_attached(&token_id);
+        self.assert_approvable(&locker_id);
+
+        self.internal_lock(&token_id, &locker_id, until.0, None);
+
+        let storage_used = env::storage_usage().saturating_sub(initial_storage_usage);
+        refund_deposit(storage_used);
+    }
+
+    #[payable]
+    pub fn nft_unlock(&mut self, token_id: TokenId) {
+        assert_one_yocto();
+        self.assert_locker(&token_id);
+
+        self.internal_unlock(&token_id);
+    }
+
+    /// Release the lock and move the NFT to `receiver_id`, e.g. a liquidator.
+    #[payable]
+    pub fn nft_seize(&mut self, token_id: TokenId, receiver_id: AccountId, memo: Option<String>) {
+        assert_one_yocto();
+        self.assert_locker(&token_id);
+
+        self.internal_unlock(&token_id);
+
+        // the locker transfers the token like an approved account would
+        let locker_id = env::predecessor_account_id();
+        self.assert_approvable(&locker_id);
+        self.assert_transfer_without_payout_allowed(&locker_id, &token_id);
+
+        self.assert_transferable(&token_id);
+        let token = self.tokens_by_id.get(&token_id).unwrap();
+        let (previous_token, _, storage_released) =
+            self.internal_move_token(&token_id, token, &receiver_id, Some(locker_id), memo);
+
+        refund_storage_released(previous_token.owner_id, storage_released);
+    }
+
+    /// Return the lock of the NFT if it is still active.
+    pub fn nft_lock_of(&self, token_id: TokenId) -> Option<TokenLock> {
+        self.token_locks
+            .get(&token_id)
+            .filter(|lock| env::block_timestamp() < lock.until.0)
+    }
+}
+
+impl NftContract {
+    pub(crate) fn assert_not_locked(&self, token_id: &TokenId) {
+        assert!(
+            self.nft_lock_of(token_id.to_string()).is_none(),
+            "Token is locked"
+        );
+    }
+
+    fn assert_locker(&self, token_id: &TokenId) {
+        let lock = self
+            .nft_lock_of(token_id.to_string())
+            .expect("Token is not locked");
+
+        assert_eq!(
+            env::predecessor_account_id(),
+            lock.locker_id,
+            "Predecessor should be the locker of NFT"
+        );
+    }
+
+    pub(crate) fn internal_lock(
+        &mut self,
+        token_id: &TokenId,
+        locker_id: &AccountId,
+        until: u64,
+        swap_id: Option<SwapId>,
+    ) {
+        self.token_locks.insert(
+            token_id,
+            &TokenLock {
+                locker_id: locker_id.clone(),
+                until: U64(until),
+                swap_id: swap_id.map(U64),
+            },
+        );
+
+        let nft_lock_log = EventLog {
+            standard: NFT_STANDARD_NAME.to_string(),
+            version: NFT_METADATA_SPEC.to_string(),
+            event: EventLogVariant::NftLock(vec![NftLockLog {
+                token_id: token_id.to_string(),
+                locker_id: locker_id.to_string(),
+                until: U64(until),
+            }]),
+        };
+
+        env::log_str(&nft_lock_log.to_string());
+    }
+
+    pub(crate) fn internal_unlock(&mut self, token_id: &TokenId) {
+        if let Some(lock) = self.token_locks.remove(token_id) {
+            let nft_unlock_log = EventLog {
+                standard: NFT_STANDARD_NAME.to_string(),
+                version: NFT_METADATA_SPEC.to_string(),
+                event: EventLogVariant::NftUnlock(vec![NftUnlockLog {
+                    token_id: token_id.to_string(),
+                    locker_id: lock.locker_id.to_string(),
+                }]),
+            };
+
+            env::log_str(&nft_unlock_log.to_string());
+        }
+    }
+}
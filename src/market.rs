@@ -0,0 +1,217 @@
+use crate::*;
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct Listing {
+    pub owner_id: AccountId,
+    /// price in yoctoNEAR
+    pub price: Balance,
+    /// block timestamp in nanoseconds after which the NFT can't be bought anymore
+    pub expires_at: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct JsonListing {
+    pub token_id: TokenId,
+    pub owner_id: AccountId,
+    pub price: U128,
+    pub expires_at: Option<U64>,
+}
+
+#[near_bindgen]
+impl NftContract {
+    /// Put the NFT on sale for `price` yoctoNEAR, replacing any previous listing.
+    #[payable]
+    pub fn nft_list(&mut self, token_id: TokenId, price: U128, expires_at: Option<U64>) {
+        assert_at_least_one_yocto();
+        let initial_storage_usage = env::storage_usage();
+
+        let token = self
+            .tokens_by_id
+            .get(&token_id)
+            .expect("Token doesn't exist");
+
+        assert_eq!(
+            env::predecessor_account_id(),
+            token.owner_id,
+            "Predecessor should be the owner of NFT"
+        );
+        self.assert_not_soulbound(&token_id);
+        self.assert_not_locked(&token_id);
+        self.assert_not_attached(&token_id);
+        assert!(price.0 > 0, "Price must be greater than 0");
+        if let Some(expires_at) = expires_at {
+            assert!(
+                env::block_timestamp() < expires_at.0,
+                "Listing must expire in the future"
+            );
+        }
+
+        self.listings_by_id.insert(
+            &token_id,
+            &Listing {
+                owner_id: token.owner_id.clone(),
+                price: price.0,
+                expires_at: expires_at.map(|expires_at| expires_at.0),
+            },
+        );
+
+        let nft_list_log = EventLog {
+            standard: NFT_STANDARD_NAME.to_string(),
+            version: NFT_METADATA_SPEC.to_string(),
+            event: EventLogVariant::NftList(vec![NftListLog {
+                owner_id: token.owner_id.to_string(),
+                token_id: token_id.to_string(),
+                price,
+                expires_at,
+            }]),
+        };
+
+        env::log_str(&nft_list_log.to_string());
+
+        let storage_used = env::storage_usage().saturating_sub(initial_storage_usage);
+        refund_deposit(storage_used);
+    }
+
+    #[payable]
+    pub fn nft_delist(&mut self, token_id: TokenId) {
+        assert_one_yocto();
+
+        let listing = self
+            .listings_by_id
+            .get(&token_id)
+            .expect("Token is not listed");
+
+        assert_eq!(
+            env::predecessor_account_id(),
+            listing.owner_id,
+            "Predecessor should be the owner of NFT"
+        );
+
+        self.internal_remove_listing(&token_id);
+    }
+
+    /// Buy a listed NFT, the price is split according to `nft_payout` and the rest is refunded.
+    #[payable]
+    pub fn nft_buy(&mut self, token_id: TokenId) {
+        let listing = self
+            .listings_by_id
+            .get(&token_id)
+            .expect("Token is not listed");
+
+        if let Some(expires_at) = listing.expires_at {
+            assert!(env::block_timestamp() < expires_at, "Listing has expired");
+        }
+
+        let deposit = env::attached_deposit();
+        assert!(
+            deposit >= listing.price,
+            "Must attach at least {} yoctoNEAR to buy the NFT",
+            listing.price
+        );
+
+        let buyer_id = env::predecessor_account_id();
+
+        // remove it first so that the transfer doesn't log a delist
+        let initial_storage_usage = env::storage_usage();
+        self.listings_by_id.remove(&token_id);
+        let storage_released = initial_storage_usage - env::storage_usage();
+
+        let (previous_token, _, approvals_storage_released) =
+            self.internal_transfer(&listing.owner_id, &buyer_id, &token_id, None, None);
+
+        refund_storage_released(
+            previous_token.owner_id.clone(),
+            storage_released + approvals_storage_released,
+        );
+
+        self.internal_pay_out(&token_id, &previous_token, listing.price);
+
+        if deposit > listing.price {
+            Promise::new(buyer_id.clone()).transfer(deposit - listing.price);
+        }
+
+        let nft_sale_log = EventLog {
+            standard: NFT_STANDARD_NAME.to_string(),
+            version: NFT_METADATA_SPEC.to_string(),
+            event: EventLogVariant::NftSale(vec![NftSaleLog {
+                seller_id: listing.owner_id.to_string(),
+                buyer_id: buyer_id.to_string(),
+                token_id: token_id.to_string(),
+                price: U128(listing.price),
+            }]),
+        };
+
+        env::log_str(&nft_sale_log.to_string());
+    }
+
+    pub fn nft_listing(&self, token_id: TokenId) -> Option<JsonListing> {
+        self.listings_by_id
+            .get(&token_id)
+            .map(|listing| json_listing(token_id, listing))
+    }
+
+    pub fn nft_listings(&self, from_index: Option<U128>, limit: Option<u64>) -> Vec<JsonListing> {
+        let start = u128::from(from_index.unwrap_or(U128(0)));
+
+        self.listings_by_id
+            .iter()
+            .skip(start as usize)
+            .take(limit.unwrap_or(50) as usize)
+            .map(|(token_id, listing)| json_listing(token_id, listing))
+            .collect()
+    }
+
+    pub fn nft_listings_supply(&self) -> U128 {
+        U128(self.listings_by_id.len() as u128)
+    }
+}
+
+impl NftContract {
+    /// Drop the listing of the NFT if any and refund its storage to the lister.
+    pub(crate) fn internal_remove_listing(&mut self, token_id: &TokenId) {
+        let initial_storage_usage = env::storage_usage();
+
+        if let Some(listing) = self.listings_by_id.remove(token_id) {
+            let storage_released = initial_storage_usage - env::storage_usage();
+            refund_storage_released(listing.owner_id.clone(), storage_released);
+
+            let nft_delist_log = EventLog {
+                standard: NFT_STANDARD_NAME.to_string(),
+                version: NFT_METADATA_SPEC.to_string(),
+                event: EventLogVariant::NftDelist(vec![NftDelistLog {
+                    owner_id: listing.owner_id.to_string(),
+                    token_id: token_id.to_string(),
+                }]),
+            };
+
+            env::log_str(&nft_delist_log.to_string());
+        }
+    }
+
+    /// Send `amount` yoctoNEAR from a sale of the NFT to the seller and royalty recipients.
+    pub(crate) fn internal_pay_out(&self, token_id: &TokenId, token: &Token, amount: Balance) {
+        let payout = self.internal_payout(
+            token_id,
+            token,
+            U128(amount),
+            MAX_ROYALTY_RECIPIENTS as u32 + 1,
+            None,
+        );
+
+        for (account_id, amount) in payout.payout {
+            if amount.0 > 0 {
+                Promise::new(account_id).transfer(amount.0);
+            }
+        }
+    }
+}
+
+fn json_listing(token_id: TokenId, listing: Listing) -> JsonListing {
+    JsonListing {
+        token_id,
+        owner_id: listing.owner_id,
+        price: U128(listing.price),
+        expires_at: listing.expires_at.map(U64),
+    }
+}
@@ -6,6 +6,10 @@ pub type TokenId = String;
 #[serde(crate = "near_sdk::serde")]
 pub struct Payout {
     pub payout: HashMap<AccountId, U128>,
+
+    /// NEP-141 contract the amounts are denominated in, NEAR when missing
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ft_contract_id: Option<AccountId>,
 }
 
 #[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize)]
@@ -23,9 +27,13 @@ pub struct NFTContractMetadata {
 #[derive(BorshSerialize, BorshDeserialize)]
 pub struct Token {
     pub owner_id: AccountId,
-    pub approved_account_ids: HashMap<AccountId, u64>,
     pub next_approval_id: u64,
     pub royalty: HashMap<AccountId, u32>,
+    /// recipients whose share changes over time, their `royalty` entry is the initial share
+    pub royalty_schedules: HashMap<AccountId, RoyaltySchedule>,
+    /// minted without a royalty of its own, so it pays the collection default
+    pub uses_default_royalty: bool,
+    pub user: Option<TokenUser>,
 }
 
 #[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
@@ -53,6 +61,9 @@ pub struct JsonToken {
     pub metadata: TokenMetadata,
     pub approved_account_ids: HashMap<AccountId, u64>,
     pub royalty: HashMap<AccountId, u32>,
+
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub attributes: HashMap<String, AttributeValue>,
 }
 
 pub trait NftContractMetadata {
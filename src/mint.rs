@@ -1,5 +1,10 @@
 use crate::*;
 
+/// make sure GAS enough to pay out
+pub const MAX_ROYALTY_RECIPIENTS: usize = 6;
+/// token ids the contract mints itself are namespaced with it, e.g. `dutch:0`
+pub const RESERVED_TOKEN_ID_SEPARATOR: char = ':';
+
 #[near_bindgen]
 impl NftContract {
     #[payable]
@@ -9,20 +14,28 @@ impl NftContract {
         metadata: TokenMetadata,
         receiver_id: AccountId,
         perpetual_royalties: Option<HashMap<AccountId, u32>>,
+        soulbound: Option<bool>,
+        royalty_schedules: Option<HashMap<AccountId, RoyaltySchedule>>,
     ) {
         assert!(
             env::attached_deposit() > 0,
             "Deposit needs to be greater than 0"
         );
+        assert!(
+            !token_id.contains(RESERVED_TOKEN_ID_SEPARATOR),
+            "Token ids containing '{}' are reserved",
+            RESERVED_TOKEN_ID_SEPARATOR
+        );
         let initial_storage_usage = env::storage_usage();
 
         let mut royalty = HashMap::default();
+        let uses_default_royalty = perpetual_royalties.is_none();
 
         if let Some(perpetual_royalties) = perpetual_royalties {
-            // make sure GAS enough to pay out
             assert!(
-                perpetual_royalties.len() <= 6,
-                "Cannot add more than 6 perpetual royalty amounts"
+                perpetual_royalties.len() <= MAX_ROYALTY_RECIPIENTS,
+                "Cannot add more than {} perpetual royalty amounts",
+                MAX_ROYALTY_RECIPIENTS
             );
 
             for (account, amount) in perpetual_royalties {
@@ -30,20 +43,78 @@ impl NftContract {
             }
         }
 
+        let royalty_schedules = royalty_schedules.unwrap_or_default();
+        assert_valid_royalty_schedules(&royalty, &royalty_schedules);
+
         let token = Token {
             owner_id: receiver_id,
-            approved_account_ids: HashMap::default(),
             next_approval_id: 0,
             royalty,
+            royalty_schedules,
+            uses_default_royalty,
+            user: None,
         };
 
+        self.internal_mint(&token_id, &token, &metadata, soulbound.unwrap_or(false));
+        // Dutch mints take a slot too, series, wraps and bundles keep their own metadata
+        self.internal_assign_reveal_index(&token_id);
+
+        let required_storage_in_bytes = env::storage_usage() - initial_storage_usage;
+
+        refund_deposit(required_storage_in_bytes);
+    }
+
+    /// Burn an NFT. Soulbound tokens can also be revoked by their issuer.
+    /// Burning a wrapped token sends the original back to its owner.
+    #[payable]
+    pub fn nft_burn(&mut self, token_id: TokenId, memo: Option<String>) {
+        assert_one_yocto();
+
+        let token = self
+            .tokens_by_id
+            .get(&token_id)
+            .expect("Token doesn't exist");
+
+        let predecessor_id = env::predecessor_account_id();
+        let mut authorized_id = None;
+
+        if predecessor_id != token.owner_id {
+            assert_eq!(
+                self.soulbound_issuer_by_id.get(&token_id),
+                Some(predecessor_id.clone()),
+                "Predecessor should be the owner or the issuer of NFT"
+            );
+            authorized_id = Some(predecessor_id.to_string());
+        }
+
+        self.internal_burn(&token_id, authorized_id, memo);
+    }
+}
+
+impl NftContract {
+    pub(crate) fn internal_mint(
+        &mut self,
+        token_id: &TokenId,
+        token: &Token,
+        metadata: &TokenMetadata,
+        soulbound: bool,
+    ) {
         assert!(
-            self.tokens_by_id.insert(&token_id, &token).is_none(),
+            self.tokens_by_id.insert(token_id, token).is_none(),
             "Token with id already exists"
         );
-        self.token_metadata_by_id.insert(&token_id, &metadata);
+        self.token_metadata_by_id.insert(token_id, metadata);
 
-        self.internal_add_token_to_owner(&token.owner_id, &token_id);
+        if soulbound {
+            // the minter is the issuer who is allowed to revoke and recover the token
+            self.soulbound_issuer_by_id
+                .insert(token_id, &env::predecessor_account_id());
+        }
+
+        self.internal_add_token_to_owner(&token.owner_id, token_id);
+        for recipient_id in token.royalty.keys() {
+            self.internal_add_token_to_royalty_recipient(recipient_id, token_id);
+        }
 
         let nft_mint_log = EventLog {
             standard: NFT_STANDARD_NAME.to_string(),
@@ -56,9 +127,5 @@ impl NftContract {
         };
 
         env::log_str(&nft_mint_log.to_string());
-
-        let required_storage_in_bytes = env::storage_usage() - initial_storage_usage;
-
-        refund_deposit(required_storage_in_bytes);
     }
 }
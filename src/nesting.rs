@@ -0,0 +1,235 @@
+use crate::*;
+
+/// make sure GAS enough to move a whole tree with its root
+pub const MAX_NESTING_DEPTH: u32 = 4;
+pub const MAX_CHILDREN_PER_TOKEN: u64 = 10;
+pub const MAX_TOKENS_PER_TREE: u32 = 20;
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TokenTree {
+    pub token_id: TokenId,
+    pub children: Vec<TokenTree>,
+}
+
+#[near_bindgen]
+impl NftContract {
+    /// Make `parent_id` own the NFT, it then moves along with the parent.
+    /// Both NFTs must belong to the predecessor.
+    #[payable]
+    pub fn nft_attach(&mut self, token_id: TokenId, parent_id: TokenId) {
+        assert_at_least_one_yocto();
+
+        let predecessor_id = env::predecessor_account_id();
+        for id in [&token_id, &parent_id] {
+            let token = self.tokens_by_id.get(id).expect("Token doesn't exist");
+            assert_eq!(
+                token.owner_id, predecessor_id,
+                "Predecessor should be the owner of NFT"
+            );
+        }
+
+        assert!(
+            self.parent_by_id.get(&token_id).is_none(),
+            "Token is already attached to a parent"
+        );
+        let parent_ancestors = self.internal_ancestors(&parent_id);
+        assert!(
+            token_id != parent_id && !parent_ancestors.contains(&token_id),
+            "Token cannot own one of its ancestors"
+        );
+        assert!(
+            parent_ancestors.len() as u32 + 1 + self.internal_height(&token_id)
+                <= MAX_NESTING_DEPTH,
+            "Cannot nest tokens more than {} levels deep",
+            MAX_NESTING_DEPTH
+        );
+        assert!(
+            self.children_by_id
+                .get(&parent_id)
+                .map_or(0, |children| children.len())
+                < MAX_CHILDREN_PER_TOKEN,
+            "Cannot attach more than {} tokens to a parent",
+            MAX_CHILDREN_PER_TOKEN
+        );
+        let root_id = parent_ancestors.last().unwrap_or(&parent_id);
+        assert!(
+            self.internal_tree_size(root_id) + self.internal_tree_size(&token_id)
+                <= MAX_TOKENS_PER_TREE,
+            "Cannot have more than {} tokens in an ownership tree",
+            MAX_TOKENS_PER_TREE
+        );
+        for id in [&token_id, &parent_id] {
+            self.assert_not_soulbound(id);
+            self.assert_not_locked(id);
+        }
+
+        // approvals of an attached token would outlive its moves with the parent
+        let (_, storage_released) = self.internal_clear_approvals(&token_id, &predecessor_id);
+        refund_storage_released(predecessor_id, storage_released);
+
+        let initial_storage_usage = env::storage_usage();
+
+        // an attached token only moves with its parent
+        self.internal_remove_listing(&token_id);
+        self.internal_attach(&token_id, &parent_id);
+
+        let storage_used = env::storage_usage().saturating_sub(initial_storage_usage);
+        refund_deposit(storage_used);
+    }
+
+    /// Detach the NFT from its parent, only the root owner can do it.
+    #[payable]
+    pub fn nft_detach(&mut self, token_id: TokenId) {
+        assert_one_yocto();
+
+        let parent_id = self
+            .parent_by_id
+            .get(&token_id)
+            .expect("Token is not attached to a parent");
+
+        assert_eq!(
+            env::predecessor_account_id(),
+            self.nft_root_owner(token_id.clone()),
+            "Predecessor should be the root owner of NFT"
+        );
+        // a locked tree must reach the locker whole
+        for ancestor_id in self.internal_ancestors(&token_id) {
+            self.assert_not_locked(&ancestor_id);
+        }
+
+        let initial_storage_usage = env::storage_usage();
+        self.internal_detach(&token_id, &parent_id);
+        let storage_released = initial_storage_usage.saturating_sub(env::storage_usage());
+        refund_storage_released(env::predecessor_account_id(), storage_released);
+    }
+
+    pub fn nft_parent_of(&self, token_id: TokenId) -> Option<TokenId> {
+        self.parent_by_id.get(&token_id)
+    }
+
+    pub fn nft_children_of(&self, token_id: TokenId) -> Vec<TokenId> {
+        self.children_by_id
+            .get(&token_id)
+            .map_or_else(Vec::new, |children| children.to_vec())
+    }
+
+    /// Owner of the top-most parent of the NFT.
+    pub fn nft_root_owner(&self, token_id: TokenId) -> AccountId {
+        let root_id = self.internal_ancestors(&token_id).pop().unwrap_or(token_id);
+
+        self.tokens_by_id
+            .get(&root_id)
+            .expect("Token doesn't exist")
+            .owner_id
+    }
+
+    /// Return the NFT with every token it owns, directly or not.
+    pub fn nft_ownership_tree(&self, token_id: TokenId) -> TokenTree {
+        assert!(
+            self.tokens_by_id.get(&token_id).is_some(),
+            "Token doesn't exist"
+        );
+
+        TokenTree {
+            children: self
+                .nft_children_of(token_id.clone())
+                .into_iter()
+                .map(|child_id| self.nft_ownership_tree(child_id))
+                .collect(),
+            token_id,
+        }
+    }
+}
+
+impl NftContract {
+    pub(crate) fn assert_not_attached(&self, token_id: &TokenId) {
+        assert!(
+            self.parent_by_id.get(token_id).is_none(),
+            "Token is attached to a parent"
+        );
+    }
+
+    /// Parents of the NFT, from the closest one up to the root.
+    fn internal_ancestors(&self, token_id: &TokenId) -> Vec<TokenId> {
+        let mut ancestors = vec![];
+        let mut current_id = token_id.clone();
+
+        while let Some(parent_id) = self.parent_by_id.get(&current_id) {
+            ancestors.push(parent_id.clone());
+            current_id = parent_id;
+        }
+
+        ancestors
+    }
+
+    /// Number of levels of tokens under the NFT.
+    fn internal_height(&self, token_id: &TokenId) -> u32 {
+        self.nft_children_of(token_id.clone())
+            .iter()
+            .map(|child_id| self.internal_height(child_id) + 1)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Number of tokens in the tree under the NFT, itself included.
+    fn internal_tree_size(&self, token_id: &TokenId) -> u32 {
+        self.nft_children_of(token_id.clone())
+            .iter()
+            .map(|child_id| self.internal_tree_size(child_id))
+            .sum::<u32>()
+            + 1
+    }
+
+    fn internal_attach(&mut self, token_id: &TokenId, parent_id: &TokenId) {
+        self.parent_by_id.insert(token_id, parent_id);
+
+        let mut children = self.children_by_id.get(parent_id).unwrap_or_else(|| {
+            UnorderedSet::new(StorageKey::ChildrenByIdInner {
+                token_id_hash: hash_token_id(parent_id),
+            })
+        });
+        children.insert(token_id);
+        self.children_by_id.insert(parent_id, &children);
+    }
+
+    fn internal_detach(&mut self, token_id: &TokenId, parent_id: &TokenId) {
+        self.parent_by_id.remove(token_id);
+
+        if let Some(mut children) = self.children_by_id.get(parent_id) {
+            children.remove(token_id);
+            if children.is_empty() {
+                self.children_by_id.remove(parent_id);
+            } else {
+                self.children_by_id.insert(parent_id, &children);
+            }
+        }
+    }
+
+    /// Move the children of an NFT that changed owner, and their own children in turn.
+    pub(crate) fn internal_move_children(
+        &mut self,
+        token_id: &TokenId,
+        from: &AccountId,
+        to: &AccountId,
+    ) {
+        for child_id in self.nft_children_of(token_id.clone()) {
+            self.parent_by_id.remove(&child_id);
+            let (previous_token, _, storage_released) =
+                self.internal_transfer(from, to, &child_id, None, None);
+            refund_storage_released(previous_token.owner_id, storage_released);
+            self.parent_by_id.insert(&child_id, token_id);
+        }
+    }
+
+    /// Cut the NFT out of the ownership tree, its children become top-level tokens.
+    pub(crate) fn internal_remove_from_tree(&mut self, token_id: &TokenId) {
+        if let Some(parent_id) = self.parent_by_id.get(token_id) {
+            self.internal_detach(token_id, &parent_id);
+        }
+
+        for child_id in self.nft_children_of(token_id.clone()) {
+            self.internal_detach(&child_id, token_id);
+        }
+    }
+}
@@ -1,3 +1,6 @@
+// the transfer resolver carries everything needed to undo the transfer
+#![allow(clippy::too_many_arguments)]
+
 use crate::*;
 
 const GAS_FOR_NFT_ON_TRANSFER: Gas = Gas(10_000_000_000_000);
@@ -28,6 +31,7 @@ pub trait NftCore {
 }
 
 #[ext_contract(ext_nft_receiver)]
+#[allow(dead_code)]
 trait NftReceiver {
     /// Return `true` if the token should be returned back to the sender.
     fn nft_on_transfer(
@@ -40,6 +44,7 @@ trait NftReceiver {
 }
 
 #[ext_contract(ext_self)]
+#[allow(dead_code)]
 trait NftResolver {
     /// Return `true` if receiver successfully received NFT and `false` if NFT should be returned to original owner
     fn nft_resolve_transfer(
@@ -48,7 +53,8 @@ trait NftResolver {
         owner_id: AccountId,
         receiver_id: AccountId,
         token_id: TokenId,
-        approved_account_ids: HashMap<AccountId, u64>,
+        approved_account_ids: HashMap<AccountId, Approval>,
+        storage_released: u64,
         memo: Option<String>,
     ) -> bool;
 }
@@ -65,14 +71,12 @@ impl NftCore for NftContract {
     ) {
         assert_one_yocto();
         let sender_id = env::predecessor_account_id();
+        self.assert_transfer_without_payout_allowed(&sender_id, &token_id);
 
-        let previous_token =
+        let (previous_token, _, storage_released) =
             self.internal_transfer(&sender_id, &receiver_id, &token_id, approval_id, memo);
 
-        refund_approved_account_ids(
-            previous_token.owner_id,
-            &previous_token.approved_account_ids,
-        );
+        refund_storage_released(previous_token.owner_id, storage_released);
     }
 
     #[payable]
@@ -86,8 +90,9 @@ impl NftCore for NftContract {
     ) -> PromiseOrValue<bool> {
         assert_one_yocto();
         let sender_id = env::predecessor_account_id();
+        self.assert_transfer_without_payout_allowed(&sender_id, &token_id);
 
-        let previous_token = self.internal_transfer(
+        let (previous_token, approved_account_ids, storage_released) = self.internal_transfer(
             &sender_id,
             &receiver_id,
             &token_id,
@@ -116,7 +121,8 @@ impl NftCore for NftContract {
                         previous_token.owner_id,
                         receiver_id,
                         token_id,
-                        previous_token.approved_account_ids,
+                        approved_account_ids,
+                        storage_released,
                         memo,
                     ),
             )
@@ -125,15 +131,16 @@ impl NftCore for NftContract {
 
     fn nft_token(&self, token_id: TokenId) -> Option<JsonToken> {
         let token = self.tokens_by_id.get(&token_id);
-        let metadata = self.token_metadata_by_id.get(&token_id).unwrap();
 
         match token {
             Some(t) => Some(JsonToken {
+                metadata: self.internal_token_metadata(&token_id).unwrap(),
+                approved_account_ids: self.internal_active_approval_ids(&token_id),
+                attributes: self.attributes_by_id.get(&token_id).unwrap_or_default(),
+                // the royalty `nft_payout` applies, the default one included
+                royalty: self.internal_effective_royalty(&token_id, &t, None),
                 token_id,
                 owner_id: t.owner_id,
-                metadata,
-                approved_account_ids: t.approved_account_ids,
-                royalty: t.royalty,
             }),
             None => None,
         }
@@ -149,7 +156,8 @@ impl NftResolver for NftContract {
         owner_id: AccountId,
         receiver_id: AccountId,
         token_id: TokenId,
-        approved_account_ids: HashMap<AccountId, u64>,
+        approved_account_ids: HashMap<AccountId, Approval>,
+        storage_released: u64,
         memo: Option<String>,
     ) -> bool {
         assert_eq!(
@@ -160,7 +168,7 @@ impl NftResolver for NftContract {
         if let PromiseResult::Successful(value) = env::promise_result(0) {
             if let Ok(return_token) = serde_json::from_slice::<bool>(&value) {
                 if !return_token {
-                    refund_approved_account_ids(owner_id, &approved_account_ids);
+                    refund_storage_released(owner_id, storage_released);
                     return true;
                 }
             }
@@ -170,13 +178,13 @@ impl NftResolver for NftContract {
             if token.owner_id != receiver_id {
                 // This case probably doesn't trigger, because if internal_transfer is successful, receiver_id will own this NFT,
                 // otherwise the code will panic.
-                refund_approved_account_ids(owner_id, &approved_account_ids);
+                refund_storage_released(owner_id, storage_released);
                 return true;
             }
             token
         } else {
             // This case doesn't trigger because internal_transfer function has already checked token_id
-            // refund_approved_account_ids(owner_id, &approved_account_ids);
+            // refund_storage_released(owner_id, storage_released);
             return true;
         };
 
@@ -184,8 +192,8 @@ impl NftResolver for NftContract {
         self.internal_add_token_to_owner(&owner_id, &token_id);
 
         token.owner_id = owner_id.clone();
-        token.approved_account_ids = approved_account_ids;
         self.tokens_by_id.insert(&token_id, &token);
+        self.internal_restore_approvals(&token_id, &owner_id, approved_account_ids);
 
         let nft_transfer_log = EventLog {
             standard: NFT_STANDARD_NAME.to_string(),
@@ -194,13 +202,15 @@ impl NftResolver for NftContract {
                 authorized_id,
                 old_owner_id: receiver_id.to_string(),
                 new_owner_id: owner_id.to_string(),
-                token_ids: vec![token_id],
+                token_ids: vec![token_id.clone()],
                 memo,
             }]),
         };
 
         env::log_str(&nft_transfer_log.to_string());
 
+        self.internal_move_children(&token_id, &receiver_id, &owner_id);
+
         false
     }
 }
@@ -0,0 +1,376 @@
+use crate::*;
+
+pub type OfferId = u64;
+
+/// burning an NFT refunds all of its offers, so their number is bounded
+pub const MAX_OFFERS_PER_TOKEN: u64 = 20;
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct Offer {
+    pub bidder_id: AccountId,
+    /// `None` for an offer on any NFT of the collection
+    pub token_id: Option<TokenId>,
+    /// escrowed by the contract, in yoctoNEAR
+    pub amount: Balance,
+    /// block timestamp in nanoseconds after which the offer can be withdrawn
+    pub expires_at: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct JsonOffer {
+    pub offer_id: U64,
+    pub bidder_id: AccountId,
+    pub token_id: Option<TokenId>,
+    pub amount: U128,
+    pub expires_at: U64,
+}
+
+#[near_bindgen]
+impl NftContract {
+    /// Offer the attached deposit, minus storage, for `token_id` or for any NFT of the collection.
+    #[payable]
+    pub fn nft_make_offer(&mut self, token_id: Option<TokenId>, expires_at: U64) -> U64 {
+        let initial_storage_usage = env::storage_usage();
+        let bidder_id = env::predecessor_account_id();
+
+        if let Some(token_id) = token_id.as_ref() {
+            let token = self
+                .tokens_by_id
+                .get(token_id)
+                .expect("Token doesn't exist");
+            assert_ne!(
+                bidder_id, token.owner_id,
+                "Owner cannot make an offer on its own NFT"
+            );
+        }
+        assert!(
+            env::block_timestamp() < expires_at.0,
+            "Offer must expire in the future"
+        );
+
+        let offer_id = self.next_offer_id;
+        self.next_offer_id += 1;
+
+        let mut offer = Offer {
+            bidder_id: bidder_id.clone(),
+            token_id: token_id.clone(),
+            amount: 0,
+            expires_at: expires_at.0,
+        };
+        self.offers_by_id.insert(&offer_id, &offer);
+        self.internal_add_offer_to_indices(offer_id, &offer);
+
+        // the amount has a fixed size, storage can be measured before it is known
+        let storage_used = env::storage_usage().saturating_sub(initial_storage_usage);
+        let storage_cost = env::storage_byte_cost() * Balance::from(storage_used);
+        assert!(
+            env::attached_deposit() > storage_cost,
+            "Must attach more than {} yoctoNEAR to cover storage",
+            storage_cost
+        );
+
+        offer.amount = env::attached_deposit() - storage_cost;
+        self.offers_by_id.insert(&offer_id, &offer);
+
+        let nft_offer_log = EventLog {
+            standard: NFT_STANDARD_NAME.to_string(),
+            version: NFT_METADATA_SPEC.to_string(),
+            event: EventLogVariant::NftOffer(vec![NftOfferLog {
+                offer_id: U64(offer_id),
+                bidder_id: bidder_id.to_string(),
+                token_id,
+                amount: U128(offer.amount),
+                expires_at,
+            }]),
+        };
+
+        env::log_str(&nft_offer_log.to_string());
+
+        U64(offer_id)
+    }
+
+    /// Sell the NFT to the bidder, the offer is split according to `nft_payout`.
+    #[payable]
+    pub fn nft_accept_offer(&mut self, offer_id: U64, token_id: TokenId) {
+        assert_one_yocto();
+
+        let offer = self
+            .offers_by_id
+            .get(&offer_id.0)
+            .expect("Offer doesn't exist");
+
+        if let Some(offer_token_id) = offer.token_id.as_ref() {
+            assert_eq!(offer_token_id, &token_id, "Offer is for another NFT");
+        }
+        assert!(
+            env::block_timestamp() < offer.expires_at,
+            "Offer has expired"
+        );
+
+        let owner_id = env::predecessor_account_id();
+        let token = self
+            .tokens_by_id
+            .get(&token_id)
+            .expect("Token doesn't exist");
+        assert_eq!(
+            token.owner_id, owner_id,
+            "Predecessor should be the owner of NFT"
+        );
+
+        self.internal_remove_offer(offer_id.0, &offer);
+
+        let (previous_token, _, storage_released) =
+            self.internal_transfer(&owner_id, &offer.bidder_id, &token_id, None, None);
+
+        refund_storage_released(owner_id.clone(), storage_released);
+
+        self.internal_pay_out(&token_id, &previous_token, offer.amount);
+
+        let nft_offer_accept_log = EventLog {
+            standard: NFT_STANDARD_NAME.to_string(),
+            version: NFT_METADATA_SPEC.to_string(),
+            event: EventLogVariant::NftOfferAccept(vec![NftOfferAcceptLog {
+                offer_id,
+                owner_id: owner_id.to_string(),
+                bidder_id: offer.bidder_id.to_string(),
+                token_id,
+                amount: U128(offer.amount),
+            }]),
+        };
+
+        env::log_str(&nft_offer_accept_log.to_string());
+    }
+
+    /// Give the escrowed deposit back to the bidder once the offer has expired.
+    pub fn nft_withdraw_offer(&mut self, offer_id: U64) {
+        let offer = self
+            .offers_by_id
+            .get(&offer_id.0)
+            .expect("Offer doesn't exist");
+
+        assert_eq!(
+            env::predecessor_account_id(),
+            offer.bidder_id,
+            "Predecessor should be the bidder"
+        );
+        assert!(
+            offer.expires_at <= env::block_timestamp(),
+            "Offer has not expired yet"
+        );
+
+        self.internal_remove_offer(offer_id.0, &offer);
+        Promise::new(offer.bidder_id.clone()).transfer(offer.amount);
+
+        let nft_offer_withdraw_log = EventLog {
+            standard: NFT_STANDARD_NAME.to_string(),
+            version: NFT_METADATA_SPEC.to_string(),
+            event: EventLogVariant::NftOfferWithdraw(vec![NftOfferWithdrawLog {
+                offer_id,
+                bidder_id: offer.bidder_id.to_string(),
+            }]),
+        };
+
+        env::log_str(&nft_offer_withdraw_log.to_string());
+    }
+
+    /// Drop an offer on the caller's NFT and give the escrowed deposit back to the bidder,
+    /// so unwanted offers can't keep the NFT at `MAX_OFFERS_PER_TOKEN`.
+    #[payable]
+    pub fn nft_reject_offer(&mut self, offer_id: U64) {
+        assert_one_yocto();
+
+        let offer = self
+            .offers_by_id
+            .get(&offer_id.0)
+            .expect("Offer doesn't exist");
+        let token_id = offer
+            .token_id
+            .as_ref()
+            .expect("Collection offers cannot be rejected");
+        let token = self
+            .tokens_by_id
+            .get(token_id)
+            .expect("Token doesn't exist");
+        assert_eq!(
+            env::predecessor_account_id(),
+            token.owner_id,
+            "Predecessor should be the owner of NFT"
+        );
+
+        self.internal_remove_offer(offer_id.0, &offer);
+        Promise::new(offer.bidder_id.clone()).transfer(offer.amount);
+
+        let nft_offer_withdraw_log = EventLog {
+            standard: NFT_STANDARD_NAME.to_string(),
+            version: NFT_METADATA_SPEC.to_string(),
+            event: EventLogVariant::NftOfferWithdraw(vec![NftOfferWithdrawLog {
+                offer_id,
+                bidder_id: offer.bidder_id.to_string(),
+            }]),
+        };
+
+        env::log_str(&nft_offer_withdraw_log.to_string());
+    }
+
+    pub fn nft_offer(&self, offer_id: U64) -> Option<JsonOffer> {
+        self.offers_by_id
+            .get(&offer_id.0)
+            .map(|offer| json_offer(offer_id.0, offer))
+    }
+
+    pub fn nft_offers_for_token(
+        &self,
+        token_id: TokenId,
+        from_index: Option<U128>,
+        limit: Option<u64>,
+    ) -> Vec<JsonOffer> {
+        match self.offers_per_token.get(&token_id) {
+            Some(offer_ids) => self.internal_json_offers(&offer_ids, from_index, limit),
+            None => vec![],
+        }
+    }
+
+    pub fn nft_collection_offers(
+        &self,
+        from_index: Option<U128>,
+        limit: Option<u64>,
+    ) -> Vec<JsonOffer> {
+        self.internal_json_offers(&self.collection_offers, from_index, limit)
+    }
+
+    pub fn nft_offers_by_bidder(
+        &self,
+        account_id: AccountId,
+        from_index: Option<U128>,
+        limit: Option<u64>,
+    ) -> Vec<JsonOffer> {
+        match self.offers_per_bidder.get(&account_id) {
+            Some(offer_ids) => self.internal_json_offers(&offer_ids, from_index, limit),
+            None => vec![],
+        }
+    }
+}
+
+impl NftContract {
+    fn internal_add_offer_to_indices(&mut self, offer_id: OfferId, offer: &Offer) {
+        match offer.token_id.as_ref() {
+            Some(token_id) => {
+                let mut offer_ids = self.offers_per_token.get(token_id).unwrap_or_else(|| {
+                    UnorderedSet::new(StorageKey::OffersPerTokenInner {
+                        token_id_hash: hash_token_id(token_id),
+                    })
+                });
+                offer_ids.insert(&offer_id);
+                assert!(
+                    offer_ids.len() <= MAX_OFFERS_PER_TOKEN,
+                    "Cannot make more than {} offers on an NFT",
+                    MAX_OFFERS_PER_TOKEN
+                );
+                self.offers_per_token.insert(token_id, &offer_ids);
+            }
+            None => {
+                self.collection_offers.insert(&offer_id);
+            }
+        }
+
+        let mut offer_ids = self
+            .offers_per_bidder
+            .get(&offer.bidder_id)
+            .unwrap_or_else(|| {
+                UnorderedSet::new(StorageKey::OffersPerBidderInner {
+                    account_id_hash: hash_account_id(&offer.bidder_id),
+                })
+            });
+        offer_ids.insert(&offer_id);
+        self.offers_per_bidder.insert(&offer.bidder_id, &offer_ids);
+    }
+
+    /// Drop every offer on a burned NFT and give the escrowed deposits back to the bidders.
+    pub(crate) fn internal_cancel_offers_for_token(&mut self, token_id: &TokenId) {
+        let offer_ids = match self.offers_per_token.get(token_id) {
+            Some(offer_ids) => offer_ids.to_vec(),
+            None => return,
+        };
+
+        let mut withdraw_logs = vec![];
+        for offer_id in offer_ids {
+            let offer = self.offers_by_id.get(&offer_id).unwrap();
+            self.internal_remove_offer(offer_id, &offer);
+            Promise::new(offer.bidder_id.clone()).transfer(offer.amount);
+
+            withdraw_logs.push(NftOfferWithdrawLog {
+                offer_id: U64(offer_id),
+                bidder_id: offer.bidder_id.to_string(),
+            });
+        }
+
+        let nft_offer_withdraw_log = EventLog {
+            standard: NFT_STANDARD_NAME.to_string(),
+            version: NFT_METADATA_SPEC.to_string(),
+            event: EventLogVariant::NftOfferWithdraw(withdraw_logs),
+        };
+
+        env::log_str(&nft_offer_withdraw_log.to_string());
+    }
+
+    /// Drop the offer and refund its storage to the bidder, the escrowed amount is left to the caller.
+    fn internal_remove_offer(&mut self, offer_id: OfferId, offer: &Offer) {
+        let initial_storage_usage = env::storage_usage();
+        self.offers_by_id.remove(&offer_id);
+
+        match offer.token_id.as_ref() {
+            Some(token_id) => {
+                if let Some(mut offer_ids) = self.offers_per_token.get(token_id) {
+                    offer_ids.remove(&offer_id);
+                    if offer_ids.is_empty() {
+                        self.offers_per_token.remove(token_id);
+                    } else {
+                        self.offers_per_token.insert(token_id, &offer_ids);
+                    }
+                }
+            }
+            None => {
+                self.collection_offers.remove(&offer_id);
+            }
+        }
+
+        if let Some(mut offer_ids) = self.offers_per_bidder.get(&offer.bidder_id) {
+            offer_ids.remove(&offer_id);
+            if offer_ids.is_empty() {
+                self.offers_per_bidder.remove(&offer.bidder_id);
+            } else {
+                self.offers_per_bidder.insert(&offer.bidder_id, &offer_ids);
+            }
+        }
+
+        let storage_released = initial_storage_usage.saturating_sub(env::storage_usage());
+        refund_storage_released(offer.bidder_id.clone(), storage_released);
+    }
+
+    fn internal_json_offers(
+        &self,
+        offer_ids: &UnorderedSet<OfferId>,
+        from_index: Option<U128>,
+        limit: Option<u64>,
+    ) -> Vec<JsonOffer> {
+        let start = u128::from(from_index.unwrap_or(U128(0)));
+
+        offer_ids
+            .iter()
+            .skip(start as usize)
+            .take(limit.unwrap_or(50) as usize)
+            .map(|offer_id| json_offer(offer_id, self.offers_by_id.get(&offer_id).unwrap()))
+            .collect()
+    }
+}
+
+fn json_offer(offer_id: OfferId, offer: Offer) -> JsonOffer {
+    JsonOffer {
+        offer_id: U64(offer_id),
+        bidder_id: offer.bidder_id,
+        token_id: offer.token_id,
+        amount: U128(offer.amount),
+        expires_at: U64(offer.expires_at),
+    }
+}
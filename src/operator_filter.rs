@@ -0,0 +1,99 @@
+use crate::*;
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum ApprovalFilterMode {
+    /// any account can be approved
+    Disabled,
+    /// only the listed accounts can be approved
+    Allowlist,
+    /// every account but the listed ones can be approved
+    Blocklist,
+}
+
+#[near_bindgen]
+impl NftContract {
+    pub fn set_approval_filter_mode(&mut self, mode: ApprovalFilterMode) {
+        self.assert_contract_owner();
+        self.approval_filter_mode = mode;
+    }
+
+    pub fn add_approval_filter_accounts(&mut self, account_ids: Vec<AccountId>) {
+        self.assert_contract_owner();
+        for account_id in account_ids.iter() {
+            self.approval_filter_accounts.insert(account_id);
+        }
+    }
+
+    pub fn remove_approval_filter_accounts(&mut self, account_ids: Vec<AccountId>) {
+        self.assert_contract_owner();
+        for account_id in account_ids.iter() {
+            self.approval_filter_accounts.remove(account_id);
+        }
+    }
+
+    /// When set, approved accounts can only move NFTs through `nft_transfer_payout`
+    /// so that royalties are always paid out.
+    pub fn set_payout_transfers_only(&mut self, payout_transfers_only: bool) {
+        self.assert_contract_owner();
+        self.payout_transfers_only = payout_transfers_only;
+    }
+
+    pub fn approval_filter_mode(&self) -> ApprovalFilterMode {
+        self.approval_filter_mode
+    }
+
+    pub fn approval_filter_accounts(
+        &self,
+        from_index: Option<U128>,
+        limit: Option<u64>,
+    ) -> Vec<AccountId> {
+        let start = u128::from(from_index.unwrap_or(U128(0)));
+        let limit = limit.unwrap_or(self.approval_filter_accounts.len());
+
+        self.approval_filter_accounts
+            .iter()
+            .skip(start as usize)
+            .take(limit as usize)
+            .collect()
+    }
+
+    pub fn is_approvable(&self, account_id: AccountId) -> bool {
+        match self.approval_filter_mode {
+            ApprovalFilterMode::Disabled => true,
+            ApprovalFilterMode::Allowlist => self.approval_filter_accounts.contains(&account_id),
+            ApprovalFilterMode::Blocklist => !self.approval_filter_accounts.contains(&account_id),
+        }
+    }
+}
+
+impl NftContract {
+    pub(crate) fn assert_approvable(&self, account_id: &AccountId) {
+        assert!(
+            self.is_approvable(account_id.clone()),
+            "{} cannot be approved on this contract",
+            account_id
+        );
+    }
+
+    /// Called by the plain transfer methods, `nft_transfer_payout` doesn't go through it.
+    pub(crate) fn assert_transfer_without_payout_allowed(
+        &self,
+        sender_id: &AccountId,
+        token_id: &TokenId,
+    ) {
+        if !self.payout_transfers_only {
+            return;
+        }
+
+        let token = self
+            .tokens_by_id
+            .get(token_id)
+            .expect("Token doesn't exist");
+
+        assert_eq!(
+            sender_id, &token.owner_id,
+            "Approved accounts must transfer through nft_transfer_payout"
+        );
+    }
+}
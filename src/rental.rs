@@ -0,0 +1,71 @@
+use crate::*;
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct TokenUser {
+    pub account_id: AccountId,
+    /// block timestamp in nanoseconds after which the user loses its rights
+    pub expires: u64,
+}
+
+#[near_bindgen]
+impl NftContract {
+    /// Give `user_id` the right to use the NFT until `expires` without transferring ownership.
+    /// Passing `None` clears the current user.
+    #[payable]
+    pub fn nft_set_user(&mut self, token_id: TokenId, user_id: Option<AccountId>, expires: U64) {
+        assert_at_least_one_yocto();
+        let initial_storage_usage = env::storage_usage();
+
+        let mut token = self
+            .tokens_by_id
+            .get(&token_id)
+            .expect("Token doesn't exist");
+
+        let predecessor_id = env::predecessor_account_id();
+        assert!(
+            self.internal_is_owner_or_approved(&token_id, &token, &predecessor_id),
+            "Predecessor should be the owner or approved for NFT"
+        );
+
+        token.user = user_id.clone().map(|account_id| TokenUser {
+            account_id,
+            expires: expires.0,
+        });
+        self.tokens_by_id.insert(&token_id, &token);
+
+        log_update_user(&token_id, user_id, expires.0);
+
+        let storage_used = env::storage_usage().saturating_sub(initial_storage_usage);
+        refund_deposit(storage_used);
+    }
+
+    /// Return the current user of the NFT, `None` if there is none or it has expired.
+    pub fn nft_user_of(&self, token_id: TokenId) -> Option<AccountId> {
+        self.tokens_by_id
+            .get(&token_id)
+            .and_then(|token| token.user)
+            .filter(|user| env::block_timestamp() < user.expires)
+            .map(|user| user.account_id)
+    }
+
+    pub fn nft_user_expires(&self, token_id: TokenId) -> Option<U64> {
+        self.tokens_by_id
+            .get(&token_id)
+            .and_then(|token| token.user)
+            .map(|user| U64(user.expires))
+    }
+}
+
+pub(crate) fn log_update_user(token_id: &TokenId, user_id: Option<AccountId>, expires: u64) {
+    let nft_update_user_log = EventLog {
+        standard: NFT_STANDARD_NAME.to_string(),
+        version: NFT_METADATA_SPEC.to_string(),
+        event: EventLogVariant::NftUpdateUser(vec![NftUpdateUserLog {
+            token_id: token_id.to_string(),
+            user_id: user_id.map(|account_id| account_id.to_string()),
+            expires: U64(expires),
+        }]),
+    };
+
+    env::log_str(&nft_update_user_log.to_string());
+}
@@ -0,0 +1,116 @@
+use crate::*;
+
+/// Provenance commitment given at init for a drop that is revealed later.
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct DelayedRevealConfig {
+    /// sha256 of the JSON-serialized list of final `TokenMetadata`
+    pub provenance_hash: Base64VecU8,
+    pub placeholder: TokenMetadata,
+    /// number of entries in the committed list, `nft_mint` stops at it
+    pub supply: u64,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct DelayedReveal {
+    pub provenance_hash: Base64VecU8,
+    pub placeholder: TokenMetadata,
+    pub supply: u64,
+    /// number of tokens minted by `nft_mint`, each one owns a slot in the final list
+    pub minted_count: u64,
+    /// set once at reveal time, token with slot `i` gets `metadata_list[(i + offset) % len]`
+    pub offset: Option<u64>,
+}
+
+impl From<DelayedRevealConfig> for DelayedReveal {
+    fn from(config: DelayedRevealConfig) -> Self {
+        Self {
+            provenance_hash: config.provenance_hash,
+            placeholder: config.placeholder,
+            supply: config.supply,
+            minted_count: 0,
+            offset: None,
+        }
+    }
+}
+
+#[near_bindgen]
+impl NftContract {
+    /// Reveal the final metadata of a drop.
+    /// `metadata_list` must hash to the provenance hash committed at init.
+    pub fn nft_reveal(&mut self, metadata_list: Vec<TokenMetadata>) {
+        self.assert_contract_owner();
+
+        let mut delayed_reveal = self
+            .delayed_reveal
+            .get()
+            .expect("Delayed reveal is not enabled");
+
+        assert!(
+            delayed_reveal.offset.is_none(),
+            "Metadata is already revealed"
+        );
+        assert_eq!(
+            metadata_list.len() as u64,
+            delayed_reveal.supply,
+            "Metadata list must have one entry per committed token"
+        );
+
+        let serialized_list = serde_json::to_vec(&metadata_list).unwrap();
+        assert_eq!(
+            env::sha256(&serialized_list),
+            delayed_reveal.provenance_hash.0,
+            "Metadata list doesn't match the provenance hash"
+        );
+
+        let mut seed = [0u8; 8];
+        seed.copy_from_slice(&env::random_seed()[..8]);
+        let offset = u64::from_le_bytes(seed) % metadata_list.len() as u64;
+
+        for metadata in metadata_list.iter() {
+            self.revealed_metadata.push(metadata);
+        }
+
+        delayed_reveal.offset = Some(offset);
+        self.delayed_reveal.set(&delayed_reveal);
+
+        env::log_str(&format!("Revealed with offset {}", offset));
+    }
+
+    pub fn nft_reveal_info(&self) -> Option<DelayedReveal> {
+        self.delayed_reveal.get()
+    }
+}
+
+impl NftContract {
+    /// Reserve a slot in the final metadata list for a token of the drop.
+    pub(crate) fn internal_assign_reveal_index(&mut self, token_id: &TokenId) {
+        if let Some(mut delayed_reveal) = self.delayed_reveal.get() {
+            assert!(
+                delayed_reveal.minted_count < delayed_reveal.supply,
+                "Drop is sold out"
+            );
+            self.reveal_index_by_id
+                .insert(token_id, &delayed_reveal.minted_count);
+            delayed_reveal.minted_count += 1;
+            self.delayed_reveal.set(&delayed_reveal);
+        }
+    }
+
+    pub(crate) fn internal_token_metadata(&self, token_id: &TokenId) -> Option<TokenMetadata> {
+        if let Some(reveal_index) = self.reveal_index_by_id.get(token_id) {
+            let delayed_reveal = self.delayed_reveal.get().unwrap();
+
+            return match delayed_reveal.offset {
+                Some(offset) => {
+                    let len = self.revealed_metadata.len();
+                    self.revealed_metadata.get((reveal_index + offset) % len)
+                }
+                None => Some(delayed_reveal.placeholder),
+            };
+        }
+
+        self.token_metadata_by_id.get(token_id)
+    }
+}
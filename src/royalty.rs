@@ -1,5 +1,68 @@
 use crate::*;
 
+const DEFAULT_ROYALTY_UPDATE_LIMIT: u64 = 50;
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RoyaltyStep {
+    /// block timestamp in nanoseconds from which `royalty` applies
+    pub from: U64,
+    pub royalty: u32,
+}
+
+/// Share of a royalty recipient that changes over time, starting from its `Token::royalty` entry.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub enum RoyaltySchedule {
+    /// the share switches to each step once its timestamp is reached, a step to 0 ends the royalty
+    Steps(Vec<RoyaltyStep>),
+    /// the share decreases linearly from its initial value at `start` down to `floor` at `end`
+    LinearDecay { start: U64, end: U64, floor: u32 },
+}
+
+impl RoyaltySchedule {
+    pub fn royalty_at(&self, initial: u32, timestamp: u64) -> u32 {
+        match self {
+            RoyaltySchedule::Steps(steps) => steps
+                .iter()
+                .take_while(|step| step.from.0 <= timestamp)
+                .last()
+                .map_or(initial, |step| step.royalty),
+            RoyaltySchedule::LinearDecay { start, end, floor } => {
+                if timestamp <= start.0 {
+                    initial
+                } else if timestamp >= end.0 {
+                    *floor
+                } else {
+                    let elapsed = (timestamp - start.0) as u128;
+                    let duration = (end.0 - start.0) as u128;
+                    let decay = (initial - floor) as u128 * elapsed / duration;
+                    initial - decay as u32
+                }
+            }
+        }
+    }
+
+    /// Highest share the recipient can ever get.
+    fn max_royalty(&self, initial: u32) -> u32 {
+        match self {
+            RoyaltySchedule::Steps(steps) => steps
+                .iter()
+                .map(|step| step.royalty)
+                .fold(initial, u32::max),
+            RoyaltySchedule::LinearDecay { .. } => initial,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RoyaltyRecipientUpdate {
+    pub updated: u64,
+    /// NFTs where the share would merge with a scheduled one, they keep their recipient
+    pub skipped_token_ids: Vec<TokenId>,
+}
+
 pub trait NftRoyalty {
     fn nft_payout(&self, token_id: TokenId, balance: U128, max_len_payout: u32) -> Payout;
 
@@ -21,12 +84,376 @@ impl NftRoyalty for NftContract {
             .tokens_by_id
             .get(&token_id)
             .expect("Token doesn't exits");
-        let owner_id = token.owner_id;
-        let royalty = token.royalty;
+
+        self.internal_payout(&token_id, &token, balance, max_len_payout, None)
+    }
+
+    #[payable]
+    fn nft_transfer_payout(
+        &mut self,
+        receiver_id: AccountId,
+        token_id: TokenId,
+        approval_id: u64,
+        memo: Option<String>,
+        balance: U128,
+        max_len_payout: u32,
+    ) -> Payout {
+        self.internal_transfer_payout(
+            receiver_id,
+            token_id,
+            approval_id,
+            memo,
+            balance,
+            max_len_payout,
+            None,
+        )
+    }
+}
+
+#[near_bindgen]
+impl NftContract {
+    /// Same as `nft_payout` for a sale settled in the NEP-141 token `ft_contract_id`.
+    pub fn nft_payout_ft(
+        &self,
+        token_id: TokenId,
+        balance: U128,
+        max_len_payout: u32,
+        ft_contract_id: AccountId,
+    ) -> Payout {
+        self.assert_ft_payout_contract(&ft_contract_id);
+
+        let token = self
+            .tokens_by_id
+            .get(&token_id)
+            .expect("Token doesn't exist");
+
+        self.internal_payout(
+            &token_id,
+            &token,
+            balance,
+            max_len_payout,
+            Some(ft_contract_id),
+        )
+    }
+
+    /// Same as `nft_transfer_payout` for a sale settled in the NEP-141 token `ft_contract_id`.
+    #[payable]
+    #[allow(clippy::too_many_arguments)]
+    pub fn nft_transfer_payout_ft(
+        &mut self,
+        receiver_id: AccountId,
+        token_id: TokenId,
+        approval_id: u64,
+        memo: Option<String>,
+        balance: U128,
+        max_len_payout: u32,
+        ft_contract_id: AccountId,
+    ) -> Payout {
+        self.assert_ft_payout_contract(&ft_contract_id);
+
+        self.internal_transfer_payout(
+            receiver_id,
+            token_id,
+            approval_id,
+            memo,
+            balance,
+            max_len_payout,
+            Some(ft_contract_id),
+        )
+    }
+
+    pub fn add_ft_payout_contract(&mut self, ft_contract_id: AccountId) {
+        self.assert_contract_owner();
+        self.ft_payout_contracts.insert(&ft_contract_id);
+    }
+
+    pub fn remove_ft_payout_contract(&mut self, ft_contract_id: AccountId) {
+        self.assert_contract_owner();
+        self.ft_payout_contracts.remove(&ft_contract_id);
+    }
+
+    pub fn ft_payout_contracts(&self) -> Vec<AccountId> {
+        self.ft_payout_contracts.to_vec()
+    }
+
+    /// Override the royalty share of the predecessor on an NFT for sales in `ft_contract_id`,
+    /// `None` removes the override. Only a royalty recipient of the NFT can call it.
+    #[payable]
+    pub fn nft_set_ft_royalty(
+        &mut self,
+        token_id: TokenId,
+        ft_contract_id: AccountId,
+        amount: Option<u32>,
+    ) {
+        assert_at_least_one_yocto();
+        self.assert_ft_payout_contract(&ft_contract_id);
+        let initial_storage_usage = env::storage_usage();
+
+        let token = self
+            .tokens_by_id
+            .get(&token_id)
+            .expect("Token doesn't exist");
+
+        // overrides only ever belong to indexed royalty recipients
+        let recipient_id = env::predecessor_account_id();
+        assert!(
+            token.royalty.contains_key(&recipient_id),
+            "Predecessor should be a royalty recipient of NFT"
+        );
+
+        let mut ft_royalties = self.ft_royalties_by_id.get(&token_id).unwrap_or_default();
+        let mut ft_royalty = ft_royalties.remove(&ft_contract_id).unwrap_or_default();
+
+        match amount {
+            Some(amount) => {
+                // a recipient can only give up part of its share, never raise it
+                let share = token.royalty[&recipient_id];
+                let max_share = token
+                    .royalty_schedules
+                    .get(&recipient_id)
+                    .map_or(share, |schedule| schedule.max_royalty(share));
+                assert!(
+                    amount <= max_share,
+                    "Royalty override can't exceed the recipient's share of {}",
+                    max_share
+                );
+                ft_royalty.insert(recipient_id, amount);
+            }
+            None => {
+                ft_royalty.remove(&recipient_id);
+            }
+        }
+
+        // the shares left to a schedule count at the highest they can get
+        let max_royalty: HashMap<AccountId, u32> = token
+            .royalty
+            .iter()
+            .map(|(account_id, amount)| {
+                let amount = match (
+                    ft_royalty.get(account_id),
+                    token.royalty_schedules.get(account_id),
+                ) {
+                    (Some(ft_amount), _) => *ft_amount,
+                    (None, Some(schedule)) => schedule.max_royalty(*amount),
+                    (None, None) => *amount,
+                };
+                (account_id.clone(), amount)
+            })
+            .collect();
+        assert_valid_royalty(&max_royalty);
+
+        if !ft_royalty.is_empty() {
+            ft_royalties.insert(ft_contract_id, ft_royalty);
+        }
+
+        if ft_royalties.is_empty() {
+            self.ft_royalties_by_id.remove(&token_id);
+        } else {
+            self.ft_royalties_by_id.insert(&token_id, &ft_royalties);
+        }
+
+        let storage_used = env::storage_usage().saturating_sub(initial_storage_usage);
+        refund_deposit(storage_used);
+    }
+
+    /// Hand the royalty share of `from` on an NFT over to `to`. Only `from` can call it.
+    #[payable]
+    pub fn nft_update_royalty_recipient(
+        &mut self,
+        token_id: TokenId,
+        from: AccountId,
+        to: AccountId,
+    ) {
+        assert_at_least_one_yocto();
+        assert_eq!(
+            env::predecessor_account_id(),
+            from,
+            "Predecessor should be the current royalty recipient"
+        );
+        let initial_storage_usage = env::storage_usage();
+
+        self.internal_update_royalty_recipient(&token_id, &from, &to);
+        log_royalty_update(&from, &to, vec![token_id]);
+
+        let storage_used = env::storage_usage().saturating_sub(initial_storage_usage);
+        refund_deposit(storage_used);
+    }
+
+    /// Hand the royalty share of the predecessor over to `to` on up to `limit` of its NFTs,
+    /// and in the default royalty. Call it again until no NFT is updated.
+    #[payable]
+    pub fn nft_update_royalty_recipient_all(
+        &mut self,
+        to: AccountId,
+        limit: Option<u64>,
+    ) -> RoyaltyRecipientUpdate {
+        assert_at_least_one_yocto();
+        let from = env::predecessor_account_id();
+        assert_ne!(from, to, "Royalty recipients should be different");
+        let initial_storage_usage = env::storage_usage();
+
+        let mut skipped_token_ids = vec![];
+        let token_ids: Vec<TokenId> = self
+            .tokens_per_royalty_recipient
+            .get(&from)
+            .map(|tokens_set| {
+                tokens_set
+                    .iter()
+                    .filter(|token_id| {
+                        let token = self.tokens_by_id.get(token_id).unwrap();
+                        let blocked = is_royalty_merge_blocked(&token, &from, &to);
+                        if blocked {
+                            skipped_token_ids.push(token_id.clone());
+                        }
+                        !blocked
+                    })
+                    .take(limit.unwrap_or(DEFAULT_ROYALTY_UPDATE_LIMIT) as usize)
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        for token_id in token_ids.iter() {
+            self.internal_update_royalty_recipient(token_id, &from, &to);
+        }
+
+        // NFTs without their own royalty pay the default one
+        let default_updated = self.default_royalty.contains_key(&from);
+        if default_updated {
+            move_royalty_share(&mut self.default_royalty, &from, &to);
+        }
+
+        let updated = token_ids.len() as u64;
+        if updated > 0 || default_updated {
+            log_royalty_update(&from, &to, token_ids);
+        }
+
+        let storage_used = env::storage_usage().saturating_sub(initial_storage_usage);
+        refund_deposit(storage_used);
+
+        RoyaltyRecipientUpdate {
+            updated,
+            skipped_token_ids,
+        }
+    }
+
+    /// Set the royalty applied to every NFT minted without its own, until it is frozen.
+    pub fn set_default_royalty(&mut self, royalty: HashMap<AccountId, u32>) {
+        self.assert_contract_owner();
+        assert!(!self.default_royalty_frozen, "Default royalty is frozen");
+        assert_valid_royalty(&royalty);
+
+        self.default_royalty = royalty;
+    }
+
+    pub fn freeze_default_royalty(&mut self) {
+        self.assert_contract_owner();
+        self.default_royalty_frozen = true;
+    }
+
+    pub fn nft_default_royalty(&self) -> HashMap<AccountId, u32> {
+        self.default_royalty.clone()
+    }
+
+    /// Return the royalty `nft_payout` applies to the NFT, or `nft_payout_ft` if `ft_contract_id` is given.
+    pub fn nft_royalty(
+        &self,
+        token_id: TokenId,
+        ft_contract_id: Option<AccountId>,
+    ) -> HashMap<AccountId, u32> {
+        let token = self
+            .tokens_by_id
+            .get(&token_id)
+            .expect("Token doesn't exist");
+
+        self.internal_effective_royalty(&token_id, &token, ft_contract_id.as_ref())
+    }
+
+    pub fn nft_royalty_schedules(&self, token_id: TokenId) -> HashMap<AccountId, RoyaltySchedule> {
+        self.tokens_by_id
+            .get(&token_id)
+            .expect("Token doesn't exist")
+            .royalty_schedules
+    }
+
+    pub fn nft_tokens_for_royalty_recipient(
+        &self,
+        account_id: AccountId,
+        from_index: Option<U128>,
+        limit: Option<u64>,
+    ) -> Vec<TokenId> {
+        let tokens = if let Some(tokens_set) = self.tokens_per_royalty_recipient.get(&account_id) {
+            tokens_set
+        } else {
+            return vec![];
+        };
+
+        let start = u128::from(from_index.unwrap_or(U128(0)));
+        let limit = limit.unwrap_or(tokens.len());
+
+        tokens
+            .iter()
+            .skip(start as usize)
+            .take(limit as usize)
+            .collect()
+    }
+}
+
+impl NftContract {
+    /// Royalty override for the FT if any, then the royalty of the NFT itself,
+    /// or the collection default when it has none.
+    pub(crate) fn internal_effective_royalty(
+        &self,
+        token_id: &TokenId,
+        token: &Token,
+        ft_contract_id: Option<&AccountId>,
+    ) -> HashMap<AccountId, u32> {
+        if token.uses_default_royalty {
+            return self.default_royalty.clone();
+        }
+
+        // a recipient's override replaces its share, scheduled or not
+        let ft_royalty = ft_contract_id
+            .and_then(|ft_contract_id| {
+                self.ft_royalties_by_id
+                    .get(token_id)
+                    .and_then(|mut ft_royalties| ft_royalties.remove(ft_contract_id))
+            })
+            .unwrap_or_default();
+
+        let now = env::block_timestamp();
+        token
+            .royalty
+            .iter()
+            .map(|(account_id, amount)| {
+                let amount = match (
+                    ft_royalty.get(account_id),
+                    token.royalty_schedules.get(account_id),
+                ) {
+                    (Some(ft_amount), _) => *ft_amount,
+                    (None, Some(schedule)) => schedule.royalty_at(*amount, now),
+                    (None, None) => *amount,
+                };
+                (account_id.clone(), amount)
+            })
+            .filter(|(_, amount)| *amount > 0)
+            .collect()
+    }
+
+    pub(crate) fn internal_payout(
+        &self,
+        token_id: &TokenId,
+        token: &Token,
+        balance: U128,
+        max_len_payout: u32,
+        ft_contract_id: Option<AccountId>,
+    ) -> Payout {
+        let owner_id = token.owner_id.clone();
+        let royalty = self.internal_effective_royalty(token_id, token, ft_contract_id.as_ref());
         let balance_u128 = u128::from(balance);
         let mut total_perpetual = 0;
         let mut payout_object = Payout {
             payout: HashMap::new(),
+            ft_contract_id,
         };
 
         assert!(
@@ -53,8 +480,8 @@ impl NftRoyalty for NftContract {
         payout_object
     }
 
-    #[payable]
-    fn nft_transfer_payout(
+    #[allow(clippy::too_many_arguments)]
+    fn internal_transfer_payout(
         &mut self,
         receiver_id: AccountId,
         token_id: TokenId,
@@ -62,19 +489,191 @@ impl NftRoyalty for NftContract {
         memo: Option<String>,
         balance: U128,
         max_len_payout: u32,
+        ft_contract_id: Option<AccountId>,
     ) -> Payout {
         assert_one_yocto();
+        self.assert_not_soulbound(&token_id);
 
         let sender_id = env::predecessor_account_id();
 
-        let previous_token =
+        let (previous_token, _, storage_released) =
             self.internal_transfer(&sender_id, &receiver_id, &token_id, Some(approval_id), memo);
 
-        refund_approved_account_ids(
-            previous_token.owner_id.clone(),
-            &previous_token.approved_account_ids,
+        refund_storage_released(previous_token.owner_id.clone(), storage_released);
+
+        // the seller is the previous owner, not the receiver
+        self.internal_payout(
+            &token_id,
+            &previous_token,
+            balance,
+            max_len_payout,
+            ft_contract_id,
+        )
+    }
+
+    fn assert_ft_payout_contract(&self, ft_contract_id: &AccountId) {
+        assert!(
+            self.ft_payout_contracts.contains(ft_contract_id),
+            "Payouts in {} are not supported",
+            ft_contract_id
+        );
+    }
+
+    fn internal_update_royalty_recipient(
+        &mut self,
+        token_id: &TokenId,
+        from: &AccountId,
+        to: &AccountId,
+    ) {
+        assert_ne!(from, to, "Royalty recipients should be different");
+
+        let mut token = self
+            .tokens_by_id
+            .get(token_id)
+            .expect("Token doesn't exist");
+
+        assert!(
+            !is_royalty_merge_blocked(&token, from, to),
+            "Cannot merge shares that follow a royalty schedule"
         );
+        let share = token
+            .royalty
+            .remove(from)
+            .expect("Account is not a royalty recipient of NFT");
+        let to_share = token.royalty.get(to).copied();
+        if let Some(schedule) = token.royalty_schedules.remove(from) {
+            token.royalty_schedules.insert(to.clone(), schedule);
+        }
+
+        // merging into an existing share never adds a recipient
+        *token.royalty.entry(to.clone()).or_insert(0) += share;
+        assert!(
+            token.royalty.len() <= MAX_ROYALTY_RECIPIENTS,
+            "Cannot add more than {} perpetual royalty amounts",
+            MAX_ROYALTY_RECIPIENTS
+        );
+        self.tokens_by_id.insert(token_id, &token);
+
+        self.internal_remove_token_from_royalty_recipient(from, token_id);
+        self.internal_add_token_to_royalty_recipient(to, token_id);
+
+        // overrides move too, merged with the share the other side has for that token
+        if let Some(mut ft_royalties) = self.ft_royalties_by_id.get(token_id) {
+            for ft_royalty in ft_royalties.values_mut() {
+                let from_override = ft_royalty.remove(from);
+                let to_override = ft_royalty.get(to).copied();
+                if from_override.is_some() || to_override.is_some() {
+                    let merged =
+                        from_override.unwrap_or(share) + to_override.or(to_share).unwrap_or(0);
+                    ft_royalty.insert(to.clone(), merged);
+                }
+            }
+            self.ft_royalties_by_id.insert(token_id, &ft_royalties);
+        }
+    }
+
+    pub(crate) fn internal_add_token_to_royalty_recipient(
+        &mut self,
+        account_id: &AccountId,
+        token_id: &TokenId,
+    ) {
+        let mut tokens_set = self
+            .tokens_per_royalty_recipient
+            .get(account_id)
+            .unwrap_or_else(|| {
+                UnorderedSet::new(StorageKey::TokensPerRoyaltyRecipientInner {
+                    account_id_hash: hash_account_id(account_id),
+                })
+            });
+        tokens_set.insert(token_id);
+        self.tokens_per_royalty_recipient
+            .insert(account_id, &tokens_set);
+    }
 
-        self.nft_payout(token_id, balance, max_len_payout)
+    pub(crate) fn internal_remove_token_from_royalty_recipient(
+        &mut self,
+        account_id: &AccountId,
+        token_id: &TokenId,
+    ) {
+        if let Some(mut tokens_set) = self.tokens_per_royalty_recipient.get(account_id) {
+            tokens_set.remove(token_id);
+
+            if tokens_set.is_empty() {
+                self.tokens_per_royalty_recipient.remove(account_id);
+            } else {
+                self.tokens_per_royalty_recipient
+                    .insert(account_id, &tokens_set);
+            }
+        }
     }
 }
+
+pub(crate) fn assert_valid_royalty(royalty: &HashMap<AccountId, u32>) {
+    assert!(
+        royalty.len() <= MAX_ROYALTY_RECIPIENTS,
+        "Cannot add more than {} perpetual royalty amounts",
+        MAX_ROYALTY_RECIPIENTS
+    );
+    assert!(
+        royalty.values().sum::<u32>() <= 10_000,
+        "Royalty amounts cannot exceed 100%"
+    );
+}
+
+pub(crate) fn assert_valid_royalty_schedules(
+    royalty: &HashMap<AccountId, u32>,
+    royalty_schedules: &HashMap<AccountId, RoyaltySchedule>,
+) {
+    let mut max_royalty = royalty.clone();
+
+    for (account_id, schedule) in royalty_schedules.iter() {
+        let initial = *royalty
+            .get(account_id)
+            .expect("Royalty schedules must belong to a royalty recipient");
+
+        match schedule {
+            RoyaltySchedule::Steps(steps) => assert!(
+                steps.windows(2).all(|pair| pair[0].from.0 < pair[1].from.0),
+                "Royalty steps must be in increasing time order"
+            ),
+            RoyaltySchedule::LinearDecay { start, end, floor } => {
+                assert!(start.0 < end.0, "Royalty decay must end after it starts");
+                assert!(
+                    *floor <= initial,
+                    "Royalty decay floor cannot exceed the initial share"
+                );
+            }
+        }
+
+        max_royalty.insert(account_id.clone(), schedule.max_royalty(initial));
+    }
+
+    assert_valid_royalty(&max_royalty);
+}
+
+/// Whether handing the share of `from` over to `to` would merge it with a scheduled share.
+fn is_royalty_merge_blocked(token: &Token, from: &AccountId, to: &AccountId) -> bool {
+    token.royalty.contains_key(to)
+        && (token.royalty_schedules.contains_key(from) || token.royalty_schedules.contains_key(to))
+}
+
+/// Hand the share of `from` over to `to`, merging it into the share `to` already has.
+fn move_royalty_share(royalty: &mut HashMap<AccountId, u32>, from: &AccountId, to: &AccountId) {
+    if let Some(share) = royalty.remove(from) {
+        *royalty.entry(to.clone()).or_insert(0) += share;
+    }
+}
+
+fn log_royalty_update(from: &AccountId, to: &AccountId, token_ids: Vec<TokenId>) {
+    let nft_royalty_update_log = EventLog {
+        standard: NFT_STANDARD_NAME.to_string(),
+        version: NFT_METADATA_SPEC.to_string(),
+        event: EventLogVariant::NftRoyaltyUpdate(vec![NftRoyaltyUpdateLog {
+            old_recipient_id: from.to_string(),
+            new_recipient_id: to.to_string(),
+            token_ids,
+        }]),
+    };
+
+    env::log_str(&nft_royalty_update_log.to_string());
+}
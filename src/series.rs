@@ -0,0 +1,91 @@
+use crate::*;
+
+pub type SeriesId = String;
+
+/// Group of interchangeable NFTs sharing the same metadata.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Series {
+    pub metadata: TokenMetadata,
+    /// NFTs of the series are named `{series_id}:{index}`
+    pub minted_count: u64,
+}
+
+#[near_bindgen]
+impl NftContract {
+    #[payable]
+    pub fn nft_create_series(&mut self, series_id: SeriesId, metadata: TokenMetadata) {
+        assert_at_least_one_yocto();
+        self.assert_contract_owner();
+        let initial_storage_usage = env::storage_usage();
+
+        assert!(
+            self.series_by_id
+                .insert(
+                    &series_id,
+                    &Series {
+                        metadata,
+                        minted_count: 0,
+                    },
+                )
+                .is_none(),
+            "Series with id already exists"
+        );
+
+        let storage_used = env::storage_usage().saturating_sub(initial_storage_usage);
+        refund_deposit(storage_used);
+    }
+
+    #[payable]
+    pub fn nft_mint_series(&mut self, series_id: SeriesId, receiver_id: AccountId) -> TokenId {
+        assert_at_least_one_yocto();
+        self.assert_contract_owner();
+        let initial_storage_usage = env::storage_usage();
+
+        let token_id = self.internal_mint_series(&series_id, &receiver_id);
+
+        let storage_used = env::storage_usage().saturating_sub(initial_storage_usage);
+        refund_deposit(storage_used);
+
+        token_id
+    }
+
+    pub fn nft_series(&self, series_id: SeriesId) -> Option<Series> {
+        self.series_by_id.get(&series_id)
+    }
+
+    pub fn nft_series_of(&self, token_id: TokenId) -> Option<SeriesId> {
+        self.series_of_token.get(&token_id)
+    }
+}
+
+impl NftContract {
+    pub(crate) fn internal_mint_series(
+        &mut self,
+        series_id: &SeriesId,
+        receiver_id: &AccountId,
+    ) -> TokenId {
+        let mut series = self
+            .series_by_id
+            .get(series_id)
+            .expect("Series doesn't exist");
+
+        let token_id = format!("series:{}:{}", series_id, series.minted_count);
+        let token = Token {
+            owner_id: receiver_id.clone(),
+            next_approval_id: 0,
+            royalty: HashMap::new(),
+            royalty_schedules: HashMap::new(),
+            uses_default_royalty: true,
+            user: None,
+        };
+
+        self.internal_mint(&token_id, &token, &series.metadata, false);
+        self.series_of_token.insert(&token_id, series_id);
+
+        series.minted_count += 1;
+        self.series_by_id.insert(series_id, &series);
+
+        token_id
+    }
+}
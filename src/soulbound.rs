@@ -0,0 +1,47 @@
+use crate::*;
+
+#[near_bindgen]
+impl NftContract {
+    /// Move a soulbound token to a new account of the same holder, e.g. after a lost key.
+    /// Only the issuer of the token can recover it.
+    #[payable]
+    pub fn nft_recover(&mut self, token_id: TokenId, receiver_id: AccountId) {
+        assert_one_yocto();
+
+        let issuer_id = self
+            .soulbound_issuer_by_id
+            .get(&token_id)
+            .expect("Token is not soulbound");
+
+        assert_eq!(
+            env::predecessor_account_id(),
+            issuer_id,
+            "Predecessor should be the issuer of NFT"
+        );
+
+        let token = self
+            .tokens_by_id
+            .get(&token_id)
+            .expect("Token doesn't exist");
+
+        // a lender holding the token as collateral keeps it until the lock ends
+        self.assert_not_locked(&token_id);
+
+        let (previous_token, _, storage_released) =
+            self.internal_move_token(&token_id, token, &receiver_id, Some(issuer_id), None);
+        refund_storage_released(previous_token.owner_id, storage_released);
+    }
+
+    pub fn nft_is_soulbound(&self, token_id: TokenId) -> bool {
+        self.soulbound_issuer_by_id.get(&token_id).is_some()
+    }
+}
+
+impl NftContract {
+    pub(crate) fn assert_not_soulbound(&self, token_id: &TokenId) {
+        assert!(
+            self.soulbound_issuer_by_id.get(token_id).is_none(),
+            "Soulbound token cannot be transferred or approved"
+        );
+    }
+}
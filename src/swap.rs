@@ -0,0 +1,261 @@
+use crate::*;
+
+pub type SwapId = u64;
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Swap {
+    pub proposer_id: AccountId,
+    /// locked by the contract until the proposal is accepted, cancelled or expires
+    pub offered_token_ids: Vec<TokenId>,
+    /// yoctoNEAR escrowed on top of the offered NFTs
+    pub amount: U128,
+    /// all owned by the counterparty
+    pub requested_token_ids: Vec<TokenId>,
+    /// block timestamp in nanoseconds after which the proposal can't be accepted
+    pub expires_at: U64,
+}
+
+#[near_bindgen]
+impl NftContract {
+    /// Propose to swap NFTs of the predecessor, plus the attached deposit minus storage,
+    /// against NFTs of a single other owner.
+    #[payable]
+    pub fn nft_propose_swap(
+        &mut self,
+        offered_token_ids: Vec<TokenId>,
+        requested_token_ids: Vec<TokenId>,
+        expires_at: U64,
+    ) -> U64 {
+        let initial_storage_usage = env::storage_usage();
+        let proposer_id = env::predecessor_account_id();
+
+        assert!(
+            !requested_token_ids.is_empty(),
+            "Swap must request at least one NFT"
+        );
+        assert!(
+            env::block_timestamp() < expires_at.0,
+            "Swap must expire in the future"
+        );
+
+        let swap_id = self.next_swap_id;
+        self.next_swap_id += 1;
+
+        for token_id in offered_token_ids.iter() {
+            let token = self
+                .tokens_by_id
+                .get(token_id)
+                .expect("Token doesn't exist");
+            assert_eq!(
+                token.owner_id, proposer_id,
+                "Predecessor should be the owner of offered NFTs"
+            );
+            self.assert_not_soulbound(token_id);
+            self.assert_not_locked(token_id);
+            self.assert_not_attached(token_id);
+
+            self.internal_remove_listing(token_id);
+            self.internal_lock(
+                token_id,
+                &env::current_account_id(),
+                expires_at.0,
+                Some(swap_id),
+            );
+        }
+
+        let counterparty_id = self.internal_swap_counterparty(&requested_token_ids);
+        assert_ne!(
+            counterparty_id, proposer_id,
+            "Cannot swap NFTs with yourself"
+        );
+
+        let mut swap = Swap {
+            proposer_id: proposer_id.clone(),
+            offered_token_ids,
+            amount: U128(0),
+            requested_token_ids,
+            expires_at,
+        };
+        self.swaps_by_id.insert(&swap_id, &swap);
+
+        // the amount has a fixed size, storage can be measured before it is known
+        let storage_used = env::storage_usage().saturating_sub(initial_storage_usage);
+        let storage_cost = env::storage_byte_cost() * Balance::from(storage_used);
+        assert!(
+            env::attached_deposit() >= storage_cost,
+            "Must attach {} yoctoNEAR to cover storage",
+            storage_cost
+        );
+
+        swap.amount = U128(env::attached_deposit() - storage_cost);
+        assert!(
+            !swap.offered_token_ids.is_empty() || swap.amount.0 > 0,
+            "Swap must offer NFTs or NEAR"
+        );
+        self.swaps_by_id.insert(&swap_id, &swap);
+
+        let nft_swap_propose_log = EventLog {
+            standard: NFT_STANDARD_NAME.to_string(),
+            version: NFT_METADATA_SPEC.to_string(),
+            event: EventLogVariant::NftSwapPropose(vec![NftSwapProposeLog {
+                swap_id: U64(swap_id),
+                proposer_id: proposer_id.to_string(),
+                counterparty_id: counterparty_id.to_string(),
+                offered_token_ids: swap.offered_token_ids.clone(),
+                amount: swap.amount,
+                requested_token_ids: swap.requested_token_ids,
+                expires_at,
+            }]),
+        };
+
+        env::log_str(&nft_swap_propose_log.to_string());
+
+        U64(swap_id)
+    }
+
+    /// Exchange the NFTs of the proposal, any failing transfer reverts the whole swap.
+    #[payable]
+    pub fn nft_accept_swap(&mut self, swap_id: U64) {
+        assert_one_yocto();
+
+        let swap = self
+            .swaps_by_id
+            .get(&swap_id.0)
+            .expect("Swap doesn't exist");
+
+        assert!(
+            env::block_timestamp() < swap.expires_at.0,
+            "Swap has expired"
+        );
+
+        let counterparty_id = env::predecessor_account_id();
+        assert_eq!(
+            self.internal_swap_counterparty(&swap.requested_token_ids),
+            counterparty_id,
+            "Predecessor should be the owner of requested NFTs"
+        );
+
+        self.internal_remove_swap(swap_id.0, &swap);
+
+        for token_id in swap.offered_token_ids.iter() {
+            let (previous_token, _, storage_released) =
+                self.internal_transfer(&swap.proposer_id, &counterparty_id, token_id, None, None);
+            refund_storage_released(previous_token.owner_id, storage_released);
+        }
+
+        for token_id in swap.requested_token_ids.iter() {
+            let (previous_token, _, storage_released) =
+                self.internal_transfer(&counterparty_id, &swap.proposer_id, token_id, None, None);
+            refund_storage_released(previous_token.owner_id, storage_released);
+        }
+
+        if swap.amount.0 > 0 {
+            Promise::new(counterparty_id.clone()).transfer(swap.amount.0);
+        }
+
+        let nft_swap_accept_log = EventLog {
+            standard: NFT_STANDARD_NAME.to_string(),
+            version: NFT_METADATA_SPEC.to_string(),
+            event: EventLogVariant::NftSwapAccept(vec![NftSwapAcceptLog {
+                swap_id,
+                proposer_id: swap.proposer_id.to_string(),
+                counterparty_id: counterparty_id.to_string(),
+            }]),
+        };
+
+        env::log_str(&nft_swap_accept_log.to_string());
+    }
+
+    /// Withdraw the proposal, the offered NFTs are unlocked and the NEAR is refunded.
+    #[payable]
+    pub fn nft_cancel_swap(&mut self, swap_id: U64) {
+        assert_one_yocto();
+
+        let swap = self
+            .swaps_by_id
+            .get(&swap_id.0)
+            .expect("Swap doesn't exist");
+
+        assert_eq!(
+            env::predecessor_account_id(),
+            swap.proposer_id,
+            "Predecessor should be the proposer"
+        );
+
+        self.internal_remove_swap(swap_id.0, &swap);
+
+        if swap.amount.0 > 0 {
+            Promise::new(swap.proposer_id.clone()).transfer(swap.amount.0);
+        }
+
+        let nft_swap_cancel_log = EventLog {
+            standard: NFT_STANDARD_NAME.to_string(),
+            version: NFT_METADATA_SPEC.to_string(),
+            event: EventLogVariant::NftSwapCancel(vec![NftSwapCancelLog {
+                swap_id,
+                proposer_id: swap.proposer_id.to_string(),
+            }]),
+        };
+
+        env::log_str(&nft_swap_cancel_log.to_string());
+    }
+
+    pub fn nft_swap(&self, swap_id: U64) -> Option<Swap> {
+        self.swaps_by_id.get(&swap_id.0)
+    }
+
+    pub fn nft_swaps(&self, from_index: Option<U128>, limit: Option<u64>) -> Vec<(U64, Swap)> {
+        let start = u128::from(from_index.unwrap_or(U128(0)));
+
+        self.swaps_by_id
+            .iter()
+            .skip(start as usize)
+            .take(limit.unwrap_or(50) as usize)
+            .map(|(swap_id, swap)| (U64(swap_id), swap))
+            .collect()
+    }
+}
+
+impl NftContract {
+    /// Owner shared by all the requested NFTs.
+    fn internal_swap_counterparty(&self, requested_token_ids: &[TokenId]) -> AccountId {
+        let mut owner_ids = requested_token_ids.iter().map(|token_id| {
+            self.tokens_by_id
+                .get(token_id)
+                .expect("Token doesn't exist")
+                .owner_id
+        });
+
+        let counterparty_id = owner_ids
+            .next()
+            .expect("Swap must request at least one NFT");
+        assert!(
+            owner_ids.all(|owner_id| owner_id == counterparty_id),
+            "Requested NFTs must have the same owner"
+        );
+
+        counterparty_id
+    }
+
+    /// Drop the proposal, release the locks it holds and refund its storage to the proposer.
+    fn internal_remove_swap(&mut self, swap_id: SwapId, swap: &Swap) {
+        let initial_storage_usage = env::storage_usage();
+        self.swaps_by_id.remove(&swap_id);
+
+        for token_id in swap.offered_token_ids.iter() {
+            // an expired lock may since have been replaced by someone else's
+            let is_swap_lock = self
+                .token_locks
+                .get(token_id)
+                .is_some_and(|lock| lock.swap_id == Some(U64(swap_id)));
+
+            if is_swap_lock {
+                self.internal_unlock(token_id);
+            }
+        }
+
+        let storage_released = initial_storage_usage.saturating_sub(env::storage_usage());
+        refund_storage_released(swap.proposer_id.clone(), storage_released);
+    }
+}
@@ -0,0 +1,195 @@
+use crate::*;
+
+const GAS_FOR_RELEASE: Gas = Gas(15_000_000_000_000);
+const GAS_FOR_RESOLVE_RELEASE: Gas = Gas(15_000_000_000_000);
+
+/// NFT of another NEP-171 contract escrowed by this contract.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct WrappedToken {
+    pub contract_id: AccountId,
+    pub token_id: TokenId,
+}
+
+pub trait NonFungibleTokenReceiver {
+    /// Return `true` if the token should be returned back to the sender.
+    fn nft_on_transfer(
+        &mut self,
+        sender_id: AccountId,
+        previous_owner_id: AccountId,
+        token_id: TokenId,
+        msg: String,
+    ) -> PromiseOrValue<bool>;
+}
+
+#[ext_contract(ext_foreign_nft)]
+#[allow(dead_code)]
+trait ForeignNft {
+    fn nft_transfer(
+        &mut self,
+        receiver_id: AccountId,
+        token_id: TokenId,
+        approval_id: Option<u64>,
+        memo: Option<String>,
+    );
+}
+
+#[ext_contract(ext_release_resolver)]
+#[allow(dead_code)]
+trait ReleaseResolver {
+    fn nft_resolve_release(
+        &mut self,
+        token_id: TokenId,
+        owner_id: AccountId,
+        wrapped_token: WrappedToken,
+    ) -> bool;
+}
+
+#[near_bindgen]
+impl NonFungibleTokenReceiver for NftContract {
+    /// Escrow an NFT of an allowlisted contract and mint a wrapped token to its previous owner.
+    /// The contract pays for the storage of the wrapped token.
+    #[allow(unused_variables)]
+    fn nft_on_transfer(
+        &mut self,
+        sender_id: AccountId,
+        previous_owner_id: AccountId,
+        token_id: TokenId,
+        msg: String,
+    ) -> PromiseOrValue<bool> {
+        let contract_id = env::predecessor_account_id();
+
+        if !self.wrap_contracts.contains(&contract_id) {
+            return PromiseOrValue::Value(true);
+        }
+
+        self.internal_mint_wrapped(
+            &previous_owner_id,
+            WrappedToken {
+                contract_id,
+                token_id,
+            },
+        );
+
+        PromiseOrValue::Value(false)
+    }
+}
+
+#[near_bindgen]
+impl NftContract {
+    pub fn add_wrap_contract(&mut self, contract_id: AccountId) {
+        self.assert_contract_owner();
+        assert_ne!(
+            contract_id,
+            env::current_account_id(),
+            "Cannot wrap NFTs of this contract"
+        );
+        self.wrap_contracts.insert(&contract_id);
+    }
+
+    pub fn remove_wrap_contract(&mut self, contract_id: AccountId) {
+        self.assert_contract_owner();
+        self.wrap_contracts.remove(&contract_id);
+    }
+
+    pub fn wrap_contracts(&self) -> Vec<AccountId> {
+        self.wrap_contracts.to_vec()
+    }
+
+    /// Return the escrowed original of a wrapped token.
+    pub fn nft_wrapped_token(&self, token_id: TokenId) -> Option<WrappedToken> {
+        self.wrapped_tokens_by_id.get(&token_id)
+    }
+
+    /// Mint the wrapped token back if the original couldn't be released.
+    #[private]
+    pub fn nft_resolve_release(
+        &mut self,
+        token_id: TokenId,
+        owner_id: AccountId,
+        wrapped_token: WrappedToken,
+    ) -> bool {
+        assert_eq!(
+            env::promise_results_count(),
+            1,
+            "Promise returns too many results"
+        );
+        if let PromiseResult::Successful(_) = env::promise_result(0) {
+            return true;
+        }
+
+        assert_eq!(
+            token_id,
+            wrapped_token_id(&wrapped_token),
+            "Wrapped token id mismatch"
+        );
+        self.internal_mint_wrapped(&owner_id, wrapped_token);
+
+        false
+    }
+}
+
+impl NftContract {
+    fn internal_mint_wrapped(&mut self, owner_id: &AccountId, wrapped_token: WrappedToken) {
+        let token_id = wrapped_token_id(&wrapped_token);
+        let metadata = TokenMetadata {
+            title: Some(format!(
+                "Wrapped {} from {}",
+                wrapped_token.token_id, wrapped_token.contract_id
+            )),
+            description: None,
+            media: None,
+            media_hash: None,
+            copies: None,
+            issued_at: Some(env::block_timestamp()),
+            expires_at: None,
+            starts_at: None,
+            updated_at: None,
+            extra: Some(serde_json::to_string(&wrapped_token).unwrap()),
+            reference: None,
+            reference_hash: None,
+        };
+
+        let token = Token {
+            owner_id: owner_id.clone(),
+            next_approval_id: 0,
+            royalty: HashMap::new(),
+            royalty_schedules: HashMap::new(),
+            uses_default_royalty: false,
+            user: None,
+        };
+
+        self.internal_mint(&token_id, &token, &metadata, false);
+        self.wrapped_tokens_by_id.insert(&token_id, &wrapped_token);
+    }
+
+    /// Send the original of a burned wrapped token to `receiver_id`.
+    pub(crate) fn internal_release_wrapped(
+        &mut self,
+        token_id: &TokenId,
+        receiver_id: &AccountId,
+        wrapped_token: WrappedToken,
+    ) -> Promise {
+        ext_foreign_nft::ext(wrapped_token.contract_id.clone())
+            .with_attached_deposit(1)
+            .with_static_gas(GAS_FOR_RELEASE)
+            .nft_transfer(
+                receiver_id.clone(),
+                wrapped_token.token_id.clone(),
+                None,
+                None,
+            )
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_RESOLVE_RELEASE)
+                    .nft_resolve_release(token_id.clone(), receiver_id.clone(), wrapped_token),
+            )
+    }
+}
+
+fn wrapped_token_id(wrapped_token: &WrappedToken) -> TokenId {
+    format!(
+        "wrap:{}:{}",
+        wrapped_token.contract_id, wrapped_token.token_id
+    )
+}